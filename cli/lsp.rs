@@ -0,0 +1,492 @@
+//! A language server for text proofs, see [`logic_check::text`], so that
+//! proofs can be written in any editor with the verifier checking along.
+//!
+//! It reports the lines that don't follow from any rule, shows the rule of
+//! a line on hover, completes rule names, and has code actions to write out
+//! the rule of a line and to renumber the lines.
+
+use logic_check::{
+    text::{self, Parsed, Row, RULES},
+    FitchProof, Instruction, Line, SubProof, RULE_COLUMN,
+};
+use lsp_server::{Connection, Message, Notification, Request, Response};
+use lsp_types::{
+    notification::{
+        DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument, Notification as _,
+        PublishDiagnostics,
+    },
+    request::{CodeActionRequest, Completion, HoverRequest, Request as _},
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, CompletionItem, CompletionItemKind, CompletionOptions,
+    CompletionParams, Diagnostic, DiagnosticSeverity, DidChangeTextDocumentParams,
+    DidCloseTextDocumentParams, DidOpenTextDocumentParams, Hover, HoverContents, HoverParams,
+    HoverProviderCapability, MarkupContent, MarkupKind, Position, PublishDiagnosticsParams, Range,
+    ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url,
+    WorkspaceEdit,
+};
+use std::{collections::HashMap, error::Error, ops};
+
+/// A document and what the verifier found in it.
+struct Document {
+    text: String,
+    parsed: Result<Parsed, text::Error>,
+    /// The rule of every premise and line, by number counted from 0.
+    rules: Vec<Option<Instruction>>,
+    proven: Result<bool, String>,
+}
+
+impl Document {
+    fn new(text: String) -> Self {
+        let mut parsed = text::read(&text);
+        let (rules, proven) = match &mut parsed {
+            Ok(parsed) => {
+                let proven = parsed.proof.verify();
+                (rules(&parsed.proof), proven)
+            }
+            Err(_) => (Vec::new(), Ok(false)),
+        };
+        Self {
+            text,
+            parsed,
+            rules,
+            proven,
+        }
+    }
+
+    fn line(&self, line: usize) -> &str {
+        self.text.lines().nth(line).unwrap_or_default()
+    }
+
+    /// The position of a byte in a line, in UTF-16 code units as LSP wants.
+    fn position(&self, line: usize, byte: usize) -> Position {
+        let text = self.line(line);
+        let character = text[..byte.min(text.len())].encode_utf16().count();
+        Position::new(line as u32, character as u32)
+    }
+
+    fn range(&self, line: usize, bytes: ops::Range<usize>) -> Range {
+        Range::new(
+            self.position(line, bytes.start),
+            self.position(line, bytes.end),
+        )
+    }
+
+    fn rows(&self) -> &[Row] {
+        self.parsed
+            .as_ref()
+            .map(|p| &p.rows[..])
+            .unwrap_or_default()
+    }
+
+    fn row(&self, line: u32) -> Option<&Row> {
+        self.rows().iter().find(|r| r.line == line as usize)
+    }
+
+    fn rule(&self, row: &Row) -> Option<&Instruction> {
+        self.rules.get(row.number - 1)?.as_ref()
+    }
+
+    fn diagnostics(&self) -> Vec<Diagnostic> {
+        let diagnostic = |range, severity, message| Diagnostic {
+            range,
+            severity: Some(severity),
+            source: Some("logiko".to_string()),
+            message,
+            ..Diagnostic::default()
+        };
+        let parsed = match &self.parsed {
+            Ok(parsed) => parsed,
+            Err(e) => {
+                let line = e
+                    .line
+                    .unwrap_or(self.text.lines().count().saturating_sub(1));
+                let range = self.range(line, 0..self.line(line).len());
+                return vec![diagnostic(
+                    range,
+                    DiagnosticSeverity::ERROR,
+                    e.message.clone(),
+                )];
+            }
+        };
+
+        let mut res = Vec::new();
+        for row in &parsed.rows {
+            let range = self.range(row.line, row.term.clone());
+            let term = &self.line(row.line)[row.term.clone()];
+            match self.rule(row) {
+                None | Some(Instruction::Invalid) => res.push(diagnostic(
+                    range,
+                    DiagnosticSeverity::ERROR,
+                    format!("{term} doesn't follow from any rule"),
+                )),
+                Some(rule) if self.written_rule(row) != Some(rule.to_string()) => {
+                    res.push(diagnostic(
+                        range,
+                        DiagnosticSeverity::HINT,
+                        format!("follows by {rule}"),
+                    ))
+                }
+                Some(_) => {}
+            }
+        }
+        match &self.proven {
+            Err(e) => res.push(diagnostic(
+                Range::default(),
+                DiagnosticSeverity::ERROR,
+                e.clone(),
+            )),
+            Ok(false)
+                if res
+                    .iter()
+                    .all(|d| d.severity != Some(DiagnosticSeverity::ERROR)) =>
+            {
+                let range = parsed
+                    .rows
+                    .last()
+                    .map(|r| self.range(r.line, r.term.clone()))
+                    .unwrap_or_default();
+                res.push(diagnostic(
+                    range,
+                    DiagnosticSeverity::WARNING,
+                    format!(
+                        "the proof doesn't end with the result {} outside of any sub proof",
+                        parsed.proof.result.display(true)
+                    ),
+                ));
+            }
+            Ok(_) => {}
+        }
+        res
+    }
+
+    /// The rule written after the term, with its line numbers.
+    fn written_rule(&self, row: &Row) -> Option<String> {
+        let rule = &self.line(row.line)[row.rule.clone()?];
+        Some(rule.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+
+    fn hover(&self, position: Position) -> Option<Hover> {
+        let row = self.row(position.line)?;
+        let value = match self.rule(row)? {
+            Instruction::Invalid => "Doesn't follow from any rule".to_string(),
+            rule => {
+                let rule = rule.to_string();
+                let short = rule.split_whitespace().next().unwrap_or_default();
+                let name = RULES
+                    .iter()
+                    .find(|(r, _)| *r == short)
+                    .map(|(_, name)| *name)
+                    .unwrap_or_default();
+                format!("`{rule}`: {name}")
+            }
+        };
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value,
+            }),
+            range: Some(self.range(row.line, row.term.clone())),
+        })
+    }
+
+    fn code_actions(&self, uri: &Url, range: Range) -> Vec<CodeAction> {
+        let action = |title: String, kind, edits: Vec<TextEdit>| CodeAction {
+            title,
+            kind: Some(kind),
+            edit: Some(WorkspaceEdit {
+                changes: Some(HashMap::from([(uri.clone(), edits)])),
+                ..WorkspaceEdit::default()
+            }),
+            ..CodeAction::default()
+        };
+
+        let mut res = Vec::new();
+        let selected = range.start.line as usize..=range.end.line as usize;
+        for row in self.rows().iter().filter(|r| selected.contains(&r.line)) {
+            let rule = match self.rule(row) {
+                Some(Instruction::Invalid) | None => continue,
+                Some(rule) => rule.to_string(),
+            };
+            if self.written_rule(row).as_ref() == Some(&rule) {
+                continue;
+            }
+            let edit = match &row.rule {
+                Some(written) => TextEdit::new(self.range(row.line, written.clone()), rule.clone()),
+                None => {
+                    let text = self.line(row.line);
+                    let len = text[..row.term.end].chars().count();
+                    let padding = " ".repeat(RULE_COLUMN.saturating_sub(len).max(1));
+                    let end = self.range(row.line, row.term.end..text.len());
+                    TextEdit::new(end, format!("{padding}{rule}"))
+                }
+            };
+            res.push(action(
+                format!("Insert justification {rule}"),
+                CodeActionKind::QUICKFIX,
+                vec![edit],
+            ));
+        }
+
+        let renumber = self
+            .rows()
+            .iter()
+            .filter_map(|row| {
+                let text = self.line(row.line);
+                match &row.written_number {
+                    Some(n) if text[n.clone()] == row.number.to_string() => None,
+                    Some(n) => Some(TextEdit::new(
+                        self.range(row.line, n.clone()),
+                        row.number.to_string(),
+                    )),
+                    None => {
+                        let start = text.len() - text.trim_start().len();
+                        Some(TextEdit::new(
+                            self.range(row.line, start..start),
+                            format!("{}: ", row.number),
+                        ))
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+        if !renumber.is_empty() {
+            res.push(action(
+                "Renumber lines".to_string(),
+                CodeActionKind::SOURCE,
+                renumber,
+            ));
+        }
+        res
+    }
+}
+
+/// The rule of every premise and line of a verified proof, in order.
+fn rules(proof: &FitchProof<String>) -> Vec<Option<Instruction>> {
+    fn walk(proof: &SubProof<String>, out: &mut Vec<Option<Instruction>>) {
+        for line in proof.0.iter() {
+            match line {
+                Line::Sub(s) => walk(s, out),
+                Line::Log(_, rule) => out.push(rule.clone()),
+            }
+        }
+    }
+    let mut res = vec![Some(Instruction::Premise); proof.prepositions.len()];
+    walk(&proof.proof, &mut res);
+    res
+}
+
+/// Serves the editor over standard input and output until it shuts down.
+pub fn serve() -> Result<(), Box<dyn Error + Send + Sync>> {
+    let (connection, io_threads) = Connection::stdio();
+    let capabilities = ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        completion_provider: Some(CompletionOptions::default()),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        ..ServerCapabilities::default()
+    };
+    connection.initialize(serde_json::to_value(capabilities)?)?;
+
+    let mut documents = HashMap::new();
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    break;
+                }
+                let response = respond(&documents, request);
+                connection.sender.send(Message::Response(response))?;
+            }
+            Message::Notification(notification) => {
+                let Some(uri) = update(&mut documents, notification) else {
+                    continue;
+                };
+                let diagnostics = documents
+                    .get(&uri)
+                    .map(Document::diagnostics)
+                    .unwrap_or_default();
+                let params = PublishDiagnosticsParams::new(uri, diagnostics, None);
+                connection
+                    .sender
+                    .send(Message::Notification(Notification::new(
+                        PublishDiagnostics::METHOD.to_string(),
+                        params,
+                    )))?;
+            }
+            Message::Response(_) => {}
+        }
+    }
+    // The writer only stops once the connection is gone
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Keeps track of the open documents, returning the one that changed.
+fn update(documents: &mut HashMap<Url, Document>, notification: Notification) -> Option<Url> {
+    match notification.method.as_str() {
+        DidOpenTextDocument::METHOD => {
+            let params: DidOpenTextDocumentParams =
+                serde_json::from_value(notification.params).ok()?;
+            let uri = params.text_document.uri;
+            documents.insert(uri.clone(), Document::new(params.text_document.text));
+            Some(uri)
+        }
+        DidChangeTextDocument::METHOD => {
+            let params: DidChangeTextDocumentParams =
+                serde_json::from_value(notification.params).ok()?;
+            // The whole document is sent on every change
+            let text = params.content_changes.into_iter().last()?.text;
+            let uri = params.text_document.uri;
+            documents.insert(uri.clone(), Document::new(text));
+            Some(uri)
+        }
+        DidCloseTextDocument::METHOD => {
+            let params: DidCloseTextDocumentParams =
+                serde_json::from_value(notification.params).ok()?;
+            let uri = params.text_document.uri;
+            documents.remove(&uri);
+            Some(uri)
+        }
+        _ => None,
+    }
+}
+
+fn respond(documents: &HashMap<Url, Document>, request: Request) -> Response {
+    let id = request.id.clone();
+    let result = match request.method.as_str() {
+        HoverRequest::METHOD => serde_json::from_value::<HoverParams>(request.params).map(|p| {
+            let position = p.text_document_position_params;
+            let hover = documents
+                .get(&position.text_document.uri)
+                .and_then(|d| d.hover(position.position));
+            serde_json::to_value(hover)
+        }),
+        Completion::METHOD => {
+            serde_json::from_value::<CompletionParams>(request.params).map(|_| {
+                let items = RULES
+                    .iter()
+                    .filter(|(_, name)| *name != "Invalid")
+                    .map(|(rule, name)| CompletionItem {
+                        label: rule.to_string(),
+                        kind: Some(CompletionItemKind::KEYWORD),
+                        detail: Some(name.to_string()),
+                        ..CompletionItem::default()
+                    })
+                    .collect::<Vec<_>>();
+                serde_json::to_value(items)
+            })
+        }
+        CodeActionRequest::METHOD => serde_json::from_value::<CodeActionParams>(request.params)
+            .map(|p| {
+                let actions = documents
+                    .get(&p.text_document.uri)
+                    .map(|d| d.code_actions(&p.text_document.uri, p.range))
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(CodeActionOrCommand::CodeAction)
+                    .collect::<Vec<_>>();
+                serde_json::to_value(actions)
+            }),
+        method => {
+            return Response::new_err(
+                id,
+                lsp_server::ErrorCode::MethodNotFound as i32,
+                format!("unknown method {method}"),
+            )
+        }
+    };
+    match result {
+        Ok(Ok(value)) => Response::new_ok(id, value),
+        Ok(Err(e)) | Err(e) => Response::new_err(
+            id,
+            lsp_server::ErrorCode::InvalidParams as i32,
+            e.to_string(),
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn document(text: &str) -> Document {
+        Document::new(text.to_string())
+    }
+
+    fn range(line: u32, start: u32, end: u32) -> Range {
+        Range::new(Position::new(line, start), Position::new(line, end))
+    }
+
+    /// The edits of every code action, by title.
+    fn edits(document: &Document) -> Vec<(String, Vec<TextEdit>)> {
+        let uri = Url::parse("file:///proof.txt").unwrap();
+        document
+            .code_actions(&uri, Range::new(Position::new(0, 0), Position::new(1, 0)))
+            .into_iter()
+            .map(|a| {
+                let mut changes = a.edit.unwrap().changes.unwrap();
+                (a.title, changes.remove(&uri).unwrap())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn diagnostics() {
+        let invalid = document("p ⊢ q\nq\n").diagnostics();
+        assert_eq!(invalid.len(), 1);
+        assert_eq!(invalid[0].range, range(1, 0, 1));
+        assert_eq!(invalid[0].severity, Some(DiagnosticSeverity::ERROR));
+        assert_eq!(invalid[0].message, "q doesn't follow from any rule");
+
+        let hint = document("p → q, p ⊢ q\nq\n").diagnostics();
+        assert_eq!(hint.len(), 1);
+        assert_eq!(hint[0].severity, Some(DiagnosticSeverity::HINT));
+        assert_eq!(hint[0].message, "follows by →e 2 1");
+
+        assert_eq!(document("p → q, p ⊢ q\n3: q  →e 2 1\n").diagnostics(), []);
+    }
+
+    #[test]
+    fn hover() {
+        let document = document("p → q, p ⊢ q\nq\n");
+        let hover = document.hover(Position::new(1, 0)).unwrap();
+        let HoverContents::Markup(contents) = hover.contents else {
+            panic!("expected markup");
+        };
+        assert_eq!(contents.value, "`→e 2 1`: Implication Elimination");
+        assert_eq!(hover.range, Some(range(1, 0, 1)));
+        assert!(document.hover(Position::new(0, 0)).is_none());
+    }
+
+    #[test]
+    fn code_actions() {
+        let padding = " ".repeat(RULE_COLUMN - 1);
+        assert_eq!(
+            edits(&document("p → q, p ⊢ q\nq\n")),
+            [
+                (
+                    "Insert justification →e 2 1".to_string(),
+                    vec![TextEdit::new(range(1, 1, 1), format!("{padding}→e 2 1"))]
+                ),
+                (
+                    "Renumber lines".to_string(),
+                    vec![TextEdit::new(range(1, 0, 0), "3: ".to_string())]
+                ),
+            ]
+        );
+        assert_eq!(
+            edits(&document("p → q, p ⊢ q\n5: q  →e 1 2\n")),
+            [
+                (
+                    "Insert justification →e 2 1".to_string(),
+                    vec![TextEdit::new(range(1, 6, 12), "→e 2 1".to_string())]
+                ),
+                (
+                    "Renumber lines".to_string(),
+                    vec![TextEdit::new(range(1, 0, 1), "3".to_string())]
+                ),
+            ]
+        );
+        assert_eq!(edits(&document("p → q, p ⊢ q\n3: q  →e 2 1\n")), []);
+    }
+}
@@ -0,0 +1,306 @@
+//! Checks, formats and exports proofs from the command line, so that a pile
+//! of submissions can be graded from a shell.
+//!
+//! Proofs are read from `.logiko.json` files, see [`logic_check::format`],
+//! or from text laid out like the game prints them, see [`logic_check::text`].
+//! Puzzles can also be played in the terminal, see [`tui`], and proofs
+//! written in an editor with the language server, see [`lsp`].
+
+use logic_check::{
+    format::ProofFile,
+    schedule::{self, Schedule},
+    text, FitchProof, Instruction, Line, SubProof,
+};
+use std::{
+    fs,
+    io::{self, Read},
+    process::ExitCode,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+mod lsp;
+mod tui;
+
+const USAGE: &str = "\
+Usage: logiko <command> [file...]
+
+Commands:
+  check <file>...                   Verifies the proofs and prints them annotated
+  fmt <file>...                     Rewrites the files, JSON in the newest format
+                                    and text with the rules filled in
+  export --latex|--typst <file>...  Prints the proofs as LaTeX or Typst
+  play [file]                       Plays the first puzzle of the file, or the
+                                    puzzle of the day, in the terminal
+  lsp                               Runs the language server for text proofs
+
+Files are read as JSON if they start with `{` or `[`, and as text otherwise.
+Without files, or for `-`, standard input is read and the output is printed.
+
+Exits with 1 if a proof is invalid, and with 2 if a file can't be read.";
+
+enum Format {
+    Json(ProofFile),
+    Text(FitchProof<String>),
+}
+
+impl Format {
+    fn read(content: &str) -> Result<Self, String> {
+        if content.trim_start().starts_with(['{', '[']) {
+            ProofFile::from_json(content).map(Format::Json)
+        } else {
+            text::parse(content).map(Format::Text)
+        }
+    }
+
+    /// The proofs, `None` for puzzles without one.
+    fn proofs(&self) -> Vec<Option<FitchProof<String>>> {
+        match self {
+            Format::Json(file) => file
+                .puzzles()
+                .iter()
+                .map(|p| p.proof.is_some().then(|| p.to_proof()))
+                .collect(),
+            Format::Text(proof) => vec![Some(proof.clone())],
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let Some((command, args)) = args.split_first() else {
+        eprintln!("{USAGE}");
+        return ExitCode::from(2);
+    };
+    let res = match command.as_str() {
+        "check" => files(args).and_then(|files| check(&files)),
+        "fmt" => files(args).and_then(|files| fmt(&files)),
+        "export" => match args.split_first() {
+            Some((flag, args)) if flag == "--latex" || flag == "--typst" => {
+                files(args).and_then(|files| export(&files, flag == "--latex"))
+            }
+            _ => Err(format!("expected --latex or --typst\n\n{USAGE}")),
+        },
+        "play" => play(args),
+        "lsp" => lsp::serve().map(|_| true).map_err(|e| e.to_string()),
+        "help" | "--help" | "-h" => {
+            println!("{USAGE}");
+            Ok(true)
+        }
+        _ => Err(format!("unknown command {command:?}\n\n{USAGE}")),
+    };
+    match res {
+        Ok(true) => ExitCode::SUCCESS,
+        Ok(false) => ExitCode::from(1),
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::from(2)
+        }
+    }
+}
+
+/// The name and content of every file, `-` being standard input.
+fn files(args: &[String]) -> Result<Vec<(String, String)>, String> {
+    let args = if args.is_empty() {
+        &["-".to_string()][..]
+    } else {
+        args
+    };
+    args.iter()
+        .map(|name| {
+            let content = if name == "-" {
+                let mut s = String::new();
+                io::stdin()
+                    .read_to_string(&mut s)
+                    .map(|_| s)
+                    .map_err(|e| format!("standard input: {e}"))
+            } else {
+                fs::read_to_string(name).map_err(|e| format!("{name}: {e}"))
+            }?;
+            Ok((name.clone(), content))
+        })
+        .collect()
+}
+
+/// A proof with the name to report it by, `None` for puzzles without one.
+type Named = (String, Option<FitchProof<String>>);
+
+/// Reads every proof of the files.
+fn proofs(files: &[(String, String)]) -> Result<Vec<Named>, String> {
+    let mut res = Vec::new();
+    for (name, content) in files {
+        let proofs = Format::read(content)
+            .map_err(|e| format!("{name}: {e}"))?
+            .proofs();
+        let many = proofs.len() > 1;
+        for (i, proof) in proofs.into_iter().enumerate() {
+            let name = if many {
+                format!("{name} #{}", i + 1)
+            } else {
+                name.clone()
+            };
+            res.push((name, proof));
+        }
+    }
+    Ok(res)
+}
+
+fn check(files: &[(String, String)]) -> Result<bool, String> {
+    let mut all_valid = true;
+    for (name, proof) in proofs(files)? {
+        println!("{name}:");
+        let Some(mut proof) = proof else {
+            println!("✗ has no proof\n");
+            all_valid = false;
+            continue;
+        };
+        let res = proof.verify();
+        println!("{proof}");
+        for diagnostic in diagnostics(&proof, &res) {
+            println!("{diagnostic}");
+        }
+        if res == Ok(true) {
+            println!("✓ proves {}\n", proof.sequent());
+        } else {
+            println!("✗ doesn't prove {}\n", proof.sequent());
+            all_valid = false;
+        }
+    }
+    Ok(all_valid)
+}
+
+/// What is wrong with a proof that has been verified.
+fn diagnostics(proof: &FitchProof<String>, res: &Result<bool, String>) -> Vec<String> {
+    fn walk(proof: &SubProof<String>, index: &mut usize, out: &mut Vec<String>) {
+        for line in proof.0.iter() {
+            match line {
+                Line::Sub(s) => walk(s, index, out),
+                Line::Log(l, inst) => {
+                    if matches!(inst, None | Some(Instruction::Invalid)) {
+                        out.push(format!(
+                            "line {index}: {} doesn't follow from any rule",
+                            l.display(true)
+                        ));
+                    }
+                    *index += 1;
+                }
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    walk(&proof.proof, &mut (proof.prepositions.len() + 1), &mut out);
+    match res {
+        Err(e) => out.push(e.clone()),
+        Ok(false) if out.is_empty() => out.push(format!(
+            "the proof doesn't end with the result {} outside of any sub proof",
+            proof.result.display(true)
+        )),
+        Ok(_) => {}
+    }
+    out
+}
+
+fn fmt(files: &[(String, String)]) -> Result<bool, String> {
+    for (name, content) in files {
+        let formatted = match Format::read(content).map_err(|e| format!("{name}: {e}"))? {
+            Format::Json(file) => file.to_json(),
+            Format::Text(mut proof) => {
+                let _ = proof.verify();
+                proof.to_string()
+            }
+        };
+        if name == "-" {
+            println!("{formatted}");
+        } else {
+            fs::write(name, format!("{formatted}\n")).map_err(|e| format!("{name}: {e}"))?;
+        }
+    }
+    Ok(true)
+}
+
+/// The number of today's puzzle, counted like the game does.
+fn day_since_start() -> usize {
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs();
+    schedule::day(now as i64)
+}
+
+fn play(args: &[String]) -> Result<bool, String> {
+    let (name, proof) = match args {
+        [] => {
+            let data = include_str!("../data.json");
+            let puzzles = serde_json::from_str::<Vec<FitchProof<String>>>(data).unwrap();
+            let schedule =
+                serde_json::from_str::<Schedule>(include_str!("../schedule.json")).unwrap();
+            let day = day_since_start();
+            (
+                format!("Puzzle: {day}"),
+                puzzles[schedule.puzzle(day).unwrap()].clone(),
+            )
+        }
+        [_] => {
+            let (name, content) = files(args)?.remove(0);
+            let proof = match Format::read(&content).map_err(|e| format!("{name}: {e}"))? {
+                Format::Json(file) => file
+                    .puzzles()
+                    .first()
+                    .ok_or_else(|| format!("{name}: has no puzzles"))?
+                    .to_proof(),
+                Format::Text(proof) => proof,
+            };
+            (name, proof)
+        }
+        _ => return Err(format!("expected at most one file\n\n{USAGE}")),
+    };
+    let time = tui::play(name, proof).map_err(|e| e.to_string())?;
+    if let Some(time) = time {
+        println!("Solved in {}s", time.as_secs());
+    }
+    Ok(time.is_some())
+}
+
+fn export(files: &[(String, String)], latex: bool) -> Result<bool, String> {
+    let mut all_valid = true;
+    for (name, proof) in proofs(files)? {
+        let Some(mut proof) = proof else {
+            eprintln!("{name}: has no proof");
+            all_valid = false;
+            continue;
+        };
+        // Verified first, so that the rules are filled in
+        all_valid &= proof.verify() == Ok(true);
+        if latex {
+            println!("{}", proof.latex());
+        } else {
+            println!("{}", proof.typst());
+        }
+    }
+    Ok(all_valid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The puzzles of the game are an old pack, which is a plain list.
+    #[test]
+    fn reads_game_puzzles() {
+        let data = include_str!("../data.json");
+        let Ok(Format::Json(file)) = Format::read(data) else {
+            panic!("data.json isn't read as JSON");
+        };
+        let puzzles = serde_json::from_str::<Vec<FitchProof<String>>>(data).unwrap();
+        assert_eq!(file.puzzles().len(), puzzles.len());
+        assert!(file.puzzles().iter().all(|p| p.proof.is_none()));
+    }
+
+    #[test]
+    fn reads_text() {
+        let Ok(Format::Text(proof)) = Format::read("p ⊢ p\np") else {
+            panic!("the proof isn't read as text");
+        };
+        assert_eq!(proof.prepositions.len(), 1);
+    }
+}
@@ -0,0 +1,538 @@
+//! Plays a puzzle in the terminal, with the same keys and the same checking
+//! after every edit as the game in the browser.
+
+use logic_check::{
+    FitchProof, History, Instruction, Line, LineEdit, Logic, ProofPath, SubProof, Verifier,
+    RULE_COLUMN,
+};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    layout::{Constraint, Flex, Layout},
+    style::{Color, Modifier, Style},
+    text::{Line as Row, Span},
+    widgets::{Block, Clear, Paragraph},
+    DefaultTerminal, Frame,
+};
+use std::{
+    io,
+    time::{Duration, Instant},
+};
+
+/// The key bindings, as shown in the help.
+const BINDINGS: &[(&str, &str)] = &[
+    ("&", "∧"),
+    ("|", "∨"),
+    (">", "→"),
+    ("!", "¬"),
+    ("#", "⊥"),
+    ("a-z", "A variable"),
+    ("← →", "Select the previous or next term"),
+    ("↑ ↓", "Select the previous or next line"),
+    ("Shift ↑ ↓", "Move the line up or down"),
+    ("Enter", "Insert a line below"),
+    ("Alt Enter", "Insert a line above"),
+    ("Tab", "Move the line into a new sub proof"),
+    ("Shift Tab", "Move the lines of the sub proof out of it"),
+    ("Backspace", "Clear the term, or remove the line if empty"),
+    ("Ctrl Z", "Undo"),
+    ("Ctrl Y", "Redo"),
+    ("?", "Show or hide this help"),
+    ("Esc", "Clear the selection"),
+    ("Ctrl C", "Quit"),
+];
+
+/// Something the player can do with a key press.
+#[derive(Debug, Clone, PartialEq)]
+enum Action {
+    /// Replaces the selected term.
+    Set(Logic<String>),
+    /// Empties the selected term, or removes the line if it is already empty.
+    Clear,
+    Undo,
+    Redo,
+    InsertAbove,
+    InsertBelow,
+    MoveUp,
+    MoveDown,
+    Wrap,
+    Unwrap,
+    SelectNext,
+    SelectPrev,
+    SelectNextLine,
+    SelectPrevLine,
+    Help,
+    /// Closes the help, or clears the selection.
+    Escape,
+    Quit,
+}
+
+impl Action {
+    /// The structural edit of the selected line the action makes, if any.
+    fn line_edit(&self) -> Option<LineEdit> {
+        Some(match self {
+            Action::InsertAbove => LineEdit::InsertAbove,
+            Action::InsertBelow => LineEdit::InsertBelow,
+            Action::MoveUp => LineEdit::MoveUp,
+            Action::MoveDown => LineEdit::MoveDown,
+            Action::Wrap => LineEdit::Wrap,
+            Action::Unwrap => LineEdit::Unwrap,
+            _ => return None,
+        })
+    }
+}
+
+/// The action bound to a key.
+fn action(key: KeyEvent) -> Option<Action> {
+    let empty = || Logic::Empty.into();
+    let shift = key.modifiers.contains(KeyModifiers::SHIFT);
+    if key.modifiers.contains(KeyModifiers::CONTROL) {
+        return match key.code {
+            KeyCode::Char('z') => Some(Action::Undo),
+            KeyCode::Char('y') => Some(Action::Redo),
+            KeyCode::Char('c') => Some(Action::Quit),
+            _ => None,
+        };
+    }
+    let action = match key.code {
+        KeyCode::Char('&') => Action::Set(Logic::And(empty(), empty())),
+        KeyCode::Char('|') => Action::Set(Logic::Or(empty(), empty())),
+        KeyCode::Char('>') => Action::Set(Logic::Implies(empty(), empty())),
+        KeyCode::Char('!') => Action::Set(Logic::Not(empty())),
+        KeyCode::Char('#') => Action::Set(Logic::Bottom),
+        KeyCode::Char('?') => Action::Help,
+        KeyCode::Char(c) if c.is_ascii_lowercase() => Action::Set(Logic::Variable(c.to_string())),
+        KeyCode::Left => Action::SelectPrev,
+        KeyCode::Right => Action::SelectNext,
+        KeyCode::Up if shift => Action::MoveUp,
+        KeyCode::Down if shift => Action::MoveDown,
+        KeyCode::Up => Action::SelectPrevLine,
+        KeyCode::Down => Action::SelectNextLine,
+        // Few terminals report Shift with Enter
+        KeyCode::Enter if shift || key.modifiers.contains(KeyModifiers::ALT) => Action::InsertAbove,
+        KeyCode::Enter => Action::InsertBelow,
+        KeyCode::BackTab => Action::Unwrap,
+        KeyCode::Tab => Action::Wrap,
+        KeyCode::Backspace | KeyCode::Delete => Action::Clear,
+        KeyCode::Esc => Action::Escape,
+        _ => return None,
+    };
+    Some(action)
+}
+
+struct App {
+    name: String,
+    proof: FitchProof<String>,
+    verifier: Verifier<String>,
+    history: History<String>,
+    selected: Option<ProofPath>,
+    error: Option<String>,
+    started: Instant,
+    won: Option<Duration>,
+    help: bool,
+    quit: bool,
+}
+
+/// Plays the puzzle until it is solved and the player quits, returning the
+/// time it took if it was solved.
+pub fn play(name: String, proof: FitchProof<String>) -> io::Result<Option<Duration>> {
+    let mut app = App::new(name, proof);
+    let mut terminal = ratatui::init();
+    let res = app.run(&mut terminal);
+    ratatui::restore();
+    res.map(|_| app.won)
+}
+
+impl App {
+    /// Starts on the first empty term, or else the first line.
+    fn new(name: String, proof: FitchProof<String>) -> Self {
+        let mut app = App {
+            name,
+            proof,
+            verifier: Verifier::new(),
+            history: History::new(),
+            selected: None,
+            error: None,
+            started: Instant::now(),
+            won: None,
+            help: false,
+            quit: false,
+        };
+        app.selected = app
+            .proof
+            .proof
+            .first_empty()
+            .or_else(|| app.proof.proof.next_line(&ProofPath::default()));
+        app.check(&[]);
+        app
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        while !self.quit {
+            terminal.draw(|frame| self.draw(frame))?;
+            // Redrawn every second for the timer
+            if !event::poll(Duration::from_secs(1))? {
+                continue;
+            }
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press {
+                    if let Some(action) = action(key) {
+                        self.act(action);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn check(&mut self, edit: &[usize]) {
+        match self.verifier.reverify(&mut self.proof, edit) {
+            Ok(won) => {
+                self.error = None;
+                if won {
+                    self.won = Some(self.started.elapsed());
+                }
+            }
+            Err(e) => self.error = Some(e),
+        }
+    }
+
+    /// Applies `edit` to the proof and records it, with the selection from
+    /// before the edit.
+    fn edit<R>(&mut self, edit: impl FnOnce(&mut SubProof<String>) -> R) -> R {
+        let before = self.proof.proof.clone();
+        let res = edit(&mut self.proof.proof);
+        self.history
+            .record(&before, &self.proof.proof, self.selected.clone());
+        res
+    }
+
+    fn edit_line(&mut self, c: ProofPath, edit: LineEdit) {
+        let Some(edited) = self.edit(|p| edit.apply(p, &c.line)) else {
+            return;
+        };
+        self.selected = Some(ProofPath::new(edited.selected));
+        self.check(&edited.changed);
+    }
+
+    fn set_term(&mut self, c: ProofPath, term: Logic<String>) {
+        self.edit(|p| {
+            if let Some(l) = p.term_mut(&c) {
+                *l = term;
+            }
+        });
+        // Move on to the next empty term in the same line
+        let next = self.proof.proof.next_empty(&c).filter(|n| n.line == c.line);
+        self.selected = Some(next.unwrap_or_else(|| c.clone()));
+        self.check(&c.line);
+    }
+
+    fn act(&mut self, action: Action) {
+        match action {
+            Action::Quit => self.quit = true,
+            Action::Help => self.help = !self.help,
+            Action::Escape if self.help => self.help = false,
+            _ if self.help || self.won.is_some() => {}
+            action => self.edit_proof(action),
+        }
+    }
+
+    fn edit_proof(&mut self, action: Action) {
+        let selected = self.selected.clone();
+        let term = selected
+            .clone()
+            .filter(|p| self.proof.proof.term(p).is_some());
+        let outer = term.clone().filter(|p| p.is_line());
+
+        match action {
+            Action::Set(l) => {
+                if let Some(c) = term {
+                    self.set_term(c, l);
+                }
+            }
+            Action::Clear => {
+                let Some(c) = term else { return };
+                let empty = matches!(self.proof.proof.term(&c), Some(Logic::Empty));
+                if !empty {
+                    self.set_term(c, Logic::Empty);
+                } else if c.is_line() {
+                    let prev = self.proof.proof.prev_line(&c);
+                    self.edit(|p| p.remove_line(&c.line));
+                    self.selected = prev;
+                    self.check(&c.line);
+                } else {
+                    self.selected = c.parent();
+                }
+            }
+            Action::Undo | Action::Redo => {
+                let restored = if action == Action::Undo {
+                    self.history.undo(&mut self.proof.proof)
+                } else {
+                    self.history.redo(&mut self.proof.proof)
+                };
+                if let Some(restored) = restored {
+                    self.selected = restored.selection;
+                    self.check(&restored.edit);
+                }
+            }
+            Action::InsertAbove
+            | Action::InsertBelow
+            | Action::MoveUp
+            | Action::MoveDown
+            | Action::Wrap
+            | Action::Unwrap => {
+                let (Some(c), Some(edit)) = (outer, action.line_edit()) else {
+                    return;
+                };
+                self.edit_line(c, edit);
+            }
+            Action::SelectNext | Action::SelectPrev => {
+                let proof = &self.proof.proof;
+                let path = match (&selected, &action) {
+                    (None, _) => proof.next_term(&ProofPath::default()),
+                    (Some(c), Action::SelectNext) => proof.next_term(c),
+                    (Some(c), _) => proof.prev_term(c),
+                };
+                self.selected = path.or(selected);
+            }
+            Action::SelectNextLine | Action::SelectPrevLine => {
+                let proof = &self.proof.proof;
+                let path = match (&selected, &action) {
+                    (None, _) => proof.next_line(&ProofPath::default()),
+                    (Some(c), Action::SelectNextLine) => proof.next_line(c),
+                    (Some(c), _) => proof.prev_line(c),
+                };
+                self.selected = path.or(selected);
+            }
+            Action::Escape => self.selected = None,
+            Action::Help | Action::Quit => unreachable!(),
+        }
+    }
+
+    fn draw(&self, frame: &mut Frame) {
+        let [title, body, status] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Min(1),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let title_text = match self.won {
+            Some(time) => format!("{}, solved in {}s!", self.name, time.as_secs()),
+            None => format!("{}, {}s", self.name, self.started.elapsed().as_secs()),
+        };
+        frame.render_widget(
+            Paragraph::new(title_text).style(Style::new().add_modifier(Modifier::BOLD)),
+            title,
+        );
+
+        let (rows, selected_row) = self.rows();
+        // Keeps the selected line in view
+        let scroll = selected_row
+            .map(|r| r.saturating_sub(body.height.saturating_sub(1) as usize))
+            .unwrap_or_default();
+        frame.render_widget(Paragraph::new(rows).scroll((scroll as u16, 0)), body);
+
+        let status_text = match (&self.error, self.won) {
+            (Some(e), _) => Span::styled(e.clone(), Style::new().fg(Color::Red)),
+            (None, Some(_)) => Span::raw("Ctrl C to quit"),
+            (None, None) => Span::raw("? for help, Ctrl C to quit"),
+        };
+        frame.render_widget(Paragraph::new(status_text), status);
+
+        if self.help {
+            self.draw_help(frame);
+        }
+    }
+
+    fn draw_help(&self, frame: &mut Frame) {
+        let rows = BINDINGS
+            .iter()
+            .map(|(key, description)| {
+                Row::from(vec![
+                    Span::styled(format!("{key:>12}  "), Style::new().fg(Color::Yellow)),
+                    Span::raw(*description),
+                ])
+            })
+            .collect::<Vec<_>>();
+        let [area] = Layout::vertical([Constraint::Length(rows.len() as u16 + 2)])
+            .flex(Flex::Center)
+            .areas(frame.area());
+        let [area] = Layout::horizontal([Constraint::Length(62)])
+            .flex(Flex::Center)
+            .areas::<1>(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(
+            Paragraph::new(rows).block(Block::bordered().title(" Keyboard shortcuts ")),
+            area,
+        );
+    }
+
+    /// The lines of the proof, laid out like [`SubProof::display`], and the
+    /// index of the selected one.
+    fn rows(&self) -> (Vec<Row<'static>>, Option<usize>) {
+        let mut rows = Vec::new();
+        let mut result = vec![Span::raw("  ⊢  ")];
+        term_spans(
+            &self.proof.result,
+            &mut Vec::new(),
+            None,
+            true,
+            Style::new(),
+            &mut result,
+        );
+        rows.push(Row::from(result));
+        for (i, p) in self.proof.prepositions.iter().enumerate() {
+            let mut spans = vec![Span::raw(format!("{:>3}: ", i + 1))];
+            term_spans(p, &mut Vec::new(), None, true, Style::new(), &mut spans);
+            rows.push(with_rule(spans, Some(&Instruction::Premise)));
+        }
+
+        let mut selected_row = None;
+        let mut index = self.proof.prepositions.len() + 1;
+        let mut walk =
+            |line: &[usize], depth: usize, l: &Logic<String>, rule: Option<&Instruction>| {
+                let selected = self
+                    .selected
+                    .as_ref()
+                    .filter(|s| s.line == line)
+                    .map(|s| &s.term[..]);
+                if selected.is_some() {
+                    selected_row = Some(rows.len());
+                }
+                let mut spans = vec![Span::raw(format!("{index:>3}: {}", "│ ".repeat(depth)))];
+                term_spans(l, &mut Vec::new(), selected, true, Style::new(), &mut spans);
+                rows.push(with_rule(spans, rule));
+                index += 1;
+            };
+        visit(&self.proof.proof, &mut Vec::new(), &mut walk);
+        (rows, selected_row)
+    }
+}
+
+/// Calls `f` with the path, depth, term and rule of every line.
+fn visit(
+    proof: &SubProof<String>,
+    path: &mut Vec<usize>,
+    f: &mut impl FnMut(&[usize], usize, &Logic<String>, Option<&Instruction>),
+) {
+    for (i, line) in proof.0.iter().enumerate() {
+        path.push(i);
+        match line {
+            Line::Sub(s) => visit(s, path, f),
+            Line::Log(l, rule) => f(path, path.len() - 1, l, rule.as_ref()),
+        }
+        path.pop();
+    }
+}
+
+/// Pads the line and adds the rule, in red if the line is invalid.
+fn with_rule(mut spans: Vec<Span<'static>>, rule: Option<&Instruction>) -> Row<'static> {
+    let len = spans
+        .iter()
+        .map(|s| s.content.chars().count())
+        .sum::<usize>();
+    spans.push(Span::raw(
+        " ".repeat(RULE_COLUMN.saturating_sub(len).max(1)),
+    ));
+    match rule {
+        Some(Instruction::Invalid) => {
+            spans.push(Span::styled("🛑", Style::new().fg(Color::Red)));
+        }
+        Some(rule) => spans.push(Span::styled(rule.to_string(), Style::new().fg(Color::Blue))),
+        None => {}
+    }
+    Row::from(spans)
+}
+
+/// The term as written by [`Logic::display`], with the term at `selected`
+/// highlighted.
+fn term_spans(
+    term: &Logic<String>,
+    path: &mut Vec<usize>,
+    selected: Option<&[usize]>,
+    outer: bool,
+    style: Style,
+    out: &mut Vec<Span<'static>>,
+) {
+    let style = if selected == Some(&path[..]) {
+        style.add_modifier(Modifier::REVERSED)
+    } else {
+        style
+    };
+    let parens = !outer && !matches!(term, Logic::Variable(_) | Logic::Bottom);
+    let mut child = |i, t: &Logic<String>, out: &mut Vec<Span<'static>>| {
+        path.push(i);
+        term_spans(t, path, selected, false, style, out);
+        path.pop();
+    };
+    if parens {
+        out.push(Span::styled("(", style));
+    }
+    match term {
+        Logic::Variable(v) => out.push(Span::styled(v.clone(), style)),
+        Logic::Bottom => out.push(Span::styled("⊥", style)),
+        Logic::Empty => out.push(Span::styled("×", style)),
+        Logic::Not(a) => {
+            out.push(Span::styled("¬", style));
+            child(0, a, out);
+        }
+        Logic::And(a, b) | Logic::Or(a, b) | Logic::Implies(a, b) => {
+            let op = match term {
+                Logic::And(..) => " ∧ ",
+                Logic::Or(..) => " ∨ ",
+                _ => " → ",
+            };
+            child(0, a, out);
+            out.push(Span::styled(op, style));
+            child(1, b, out);
+        }
+    }
+    if parens {
+        out.push(Span::styled(")", style));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> Action {
+        action(KeyEvent::new(code, modifiers)).unwrap()
+    }
+
+    #[test]
+    fn keys_to_edits() {
+        let edits = [
+            (KeyCode::Enter, KeyModifiers::NONE, LineEdit::InsertBelow),
+            (KeyCode::Enter, KeyModifiers::ALT, LineEdit::InsertAbove),
+            (KeyCode::Up, KeyModifiers::SHIFT, LineEdit::MoveUp),
+            (KeyCode::Down, KeyModifiers::SHIFT, LineEdit::MoveDown),
+            (KeyCode::Tab, KeyModifiers::NONE, LineEdit::Wrap),
+            (KeyCode::BackTab, KeyModifiers::SHIFT, LineEdit::Unwrap),
+        ];
+        for (code, modifiers, edit) in edits {
+            assert_eq!(key(code, modifiers).line_edit(), Some(edit), "{code}");
+        }
+        assert_eq!(key(KeyCode::Up, KeyModifiers::NONE).line_edit(), None);
+        assert_eq!(
+            key(KeyCode::Char('p'), KeyModifiers::NONE).line_edit(),
+            None
+        );
+    }
+
+    #[test]
+    fn edits_selected_line() {
+        let proof = logic_check::text::parse("p ⊢ p ∧ p\np").unwrap();
+        let mut app = App::new("test".to_string(), proof);
+        assert_eq!(app.won, None);
+        app.act(Action::Wrap);
+        assert!(matches!(app.proof.proof.0[0], Line::Sub(_)));
+        assert_eq!(app.selected, Some(ProofPath::new(vec![0, 0])));
+        app.act(Action::Unwrap);
+        assert!(matches!(app.proof.proof.0[0], Line::Log(..)));
+        assert_eq!(app.selected, Some(ProofPath::new(vec![0])));
+        app.act(Action::InsertBelow);
+        assert_eq!(app.proof.proof.len(), 2);
+        assert_eq!(app.selected, Some(ProofPath::new(vec![1])));
+    }
+}
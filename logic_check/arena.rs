@@ -0,0 +1,95 @@
+use super::Logic;
+use std::{collections::HashMap, hash::Hash};
+
+/// Id of a term interned in an [`Arena`].
+///
+/// Two ids from the same arena are equal exactly when the terms are equal,
+/// so comparing and hashing terms is O(1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TermId(u32);
+
+/// A single interned term, with its children as ids.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Node<T> {
+    Variable(T),
+    And(TermId, TermId),
+    Implies(TermId, TermId),
+    Not(TermId),
+    Or(TermId, TermId),
+    Bottom,
+    Empty,
+}
+
+/// Hash-consed storage for terms. Every distinct term is only stored once.
+#[derive(Debug, Clone)]
+pub struct Arena<T> {
+    nodes: Vec<Node<T>>,
+    ids: HashMap<Node<T>, TermId>,
+}
+impl<T> Default for Arena<T> {
+    fn default() -> Self {
+        Self {
+            nodes: Vec::new(),
+            ids: HashMap::new(),
+        }
+    }
+}
+impl<T: Clone + Hash + Eq> Arena<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    pub fn node(&self, id: TermId) -> &Node<T> {
+        &self.nodes[id.0 as usize]
+    }
+
+    /// Looks up a node without adding it to the arena.
+    pub fn get(&self, node: &Node<T>) -> Option<TermId> {
+        self.ids.get(node).copied()
+    }
+
+    pub fn insert(&mut self, node: Node<T>) -> TermId {
+        if let Some(id) = self.ids.get(&node) {
+            return *id;
+        }
+        let id = TermId(self.nodes.len() as u32);
+        self.nodes.push(node.clone());
+        self.ids.insert(node, id);
+        id
+    }
+
+    pub fn intern(&mut self, term: &Logic<T>) -> TermId {
+        let node = match term {
+            Logic::Variable(v) => Node::Variable(v.clone()),
+            Logic::And(a, b) => Node::And(self.intern(a), self.intern(b)),
+            Logic::Implies(a, b) => Node::Implies(self.intern(a), self.intern(b)),
+            Logic::Not(a) => Node::Not(self.intern(a)),
+            Logic::Or(a, b) => Node::Or(self.intern(a), self.intern(b)),
+            Logic::Bottom => Node::Bottom,
+            Logic::Empty => Node::Empty,
+        };
+        self.insert(node)
+    }
+
+    pub fn to_logic(&self, id: TermId) -> Logic<T> {
+        match self.node(id) {
+            Node::Variable(v) => Logic::Variable(v.clone()),
+            Node::And(a, b) => Logic::And(self.to_logic(*a).into(), self.to_logic(*b).into()),
+            Node::Implies(a, b) => {
+                Logic::Implies(self.to_logic(*a).into(), self.to_logic(*b).into())
+            }
+            Node::Not(a) => Logic::Not(self.to_logic(*a).into()),
+            Node::Or(a, b) => Logic::Or(self.to_logic(*a).into(), self.to_logic(*b).into()),
+            Node::Bottom => Logic::Bottom,
+            Node::Empty => Logic::Empty,
+        }
+    }
+}
@@ -0,0 +1,57 @@
+use logic_check::{FitchProof, Line, Logic, SubProof, Verifier};
+use std::time::Instant;
+
+/// Builds a proof with `len` lines, where every line grows the previous term
+/// with `∧ q`, and every tenth step detours through a sub proof.
+fn chain(len: usize) -> FitchProof<&'static str> {
+    let p = || Logic::Variable("p");
+    let q = || Logic::Variable("q");
+    let log = |l: Logic<&'static str>| Line::Log(l.into(), None);
+
+    let mut lines = Vec::new();
+    let mut last = p();
+    while lines.len() < len {
+        if lines.len() % 10 == 9 {
+            let sub = SubProof::new(vec![log(Logic::Variable("r")), log(last.clone())]);
+            lines.push(Line::Sub(sub));
+            lines.push(log(Logic::Implies(
+                Logic::Variable("r").into(),
+                last.clone().into(),
+            )));
+        }
+        last = Logic::And(last.into(), q().into());
+        lines.push(log(last.clone()));
+    }
+
+    FitchProof {
+        proof: SubProof::new(lines),
+        prepositions: vec![p(), q()],
+        result: last.into(),
+    }
+}
+
+fn main() {
+    for len in [50, 100, 200] {
+        let proof = chain(len);
+        let runs = 20;
+        let start = Instant::now();
+        for _ in 0..runs {
+            let mut proof = proof.clone();
+            assert_eq!(proof.verify(), Ok(true));
+        }
+        let elapsed = start.elapsed() / runs;
+        println!("verify {len:>3} lines: {elapsed:?}");
+
+        // Edit the last line and only check that one again
+        let mut proof = proof.clone();
+        let mut verifier = Verifier::new();
+        verifier.verify(&mut proof).unwrap();
+        let last = vec![proof.proof.0.len() - 1];
+        let start = Instant::now();
+        for _ in 0..runs {
+            assert_eq!(verifier.reverify(&mut proof, &last), Ok(true));
+        }
+        let elapsed = start.elapsed() / runs;
+        println!("reverify {len:>3} lines, last edited: {elapsed:?}");
+    }
+}
@@ -0,0 +1,233 @@
+//! Structural edits of proofs.
+//!
+//! Paths are the same as for [`SubProof::remove_line`]: the index of each
+//! enclosing sub proof, followed by the index of the line (or sub proof) in
+//! the innermost one. Every edit returns the path of the line or sub proof it
+//! moved or created, as it is after the edit, or `None` if the path didn't
+//! allow the edit, in which case the proof is left unchanged.
+
+use super::{Line, Logic, SubProof};
+
+fn with_last(index_map: &[usize], last: usize) -> Vec<usize> {
+    let mut res = index_map.to_vec();
+    *res.last_mut().unwrap() = last;
+    res
+}
+
+impl<T: Clone> SubProof<T> {
+    /// The lines of the sub proof containing `index_map`, and the index
+    /// in it. The index may be out of bounds.
+    fn parent_mut(&mut self, index_map: &[usize]) -> Option<(&mut Vec<Line<T>>, usize)> {
+        match index_map {
+            [i] => Some((self.lines_mut(), *i)),
+            [i, xs @ ..] => match self.lines_mut().get_mut(*i) {
+                Some(Line::Sub(s)) => s.parent_mut(xs),
+                _ => None,
+            },
+            [] => None,
+        }
+    }
+
+    /// Inserts `line` so that it ends up at `index_map`, which may be one
+    /// past the last line of a sub proof.
+    pub fn insert_line(&mut self, index_map: &[usize], line: Line<T>) -> Option<Vec<usize>> {
+        let (lines, i) = self.parent_mut(index_map)?;
+        if i > lines.len() {
+            return None;
+        }
+        lines.insert(i, line);
+        Some(index_map.to_vec())
+    }
+
+    /// Inserts `line` before the one at `index_map`.
+    pub fn insert_before(&mut self, index_map: &[usize], line: Line<T>) -> Option<Vec<usize>> {
+        let (lines, i) = self.parent_mut(index_map)?;
+        if i >= lines.len() {
+            return None;
+        }
+        lines.insert(i, line);
+        Some(index_map.to_vec())
+    }
+
+    /// Inserts `line` after the one at `index_map`.
+    pub fn insert_after(&mut self, index_map: &[usize], line: Line<T>) -> Option<Vec<usize>> {
+        let (lines, i) = self.parent_mut(index_map)?;
+        if i >= lines.len() {
+            return None;
+        }
+        lines.insert(i + 1, line);
+        Some(with_last(index_map, i + 1))
+    }
+
+    /// Swaps the line with the one above it. The first line of a sub proof
+    /// is moved out of it, to just before the sub proof.
+    pub fn move_up(&mut self, index_map: &[usize]) -> Option<Vec<usize>> {
+        let (lines, i) = self.parent_mut(index_map)?;
+        if i >= lines.len() {
+            return None;
+        }
+        if i > 0 {
+            lines.swap(i - 1, i);
+            return Some(with_last(index_map, i - 1));
+        }
+        let parent = &index_map[..index_map.len() - 1];
+        if parent.is_empty() {
+            return None;
+        }
+        let line = lines.remove(0);
+        self.insert_line(parent, line)
+    }
+
+    /// Swaps the line with the one below it. The last line of a sub proof
+    /// is moved out of it, to just after the sub proof.
+    pub fn move_down(&mut self, index_map: &[usize]) -> Option<Vec<usize>> {
+        let (lines, i) = self.parent_mut(index_map)?;
+        if i >= lines.len() {
+            return None;
+        }
+        if i + 1 < lines.len() {
+            lines.swap(i, i + 1);
+            return Some(with_last(index_map, i + 1));
+        }
+        let parent = &index_map[..index_map.len() - 1];
+        if parent.is_empty() {
+            return None;
+        }
+        let line = lines.remove(i);
+        let after = with_last(parent, parent[parent.len() - 1] + 1);
+        self.insert_line(&after, line)
+    }
+
+    /// Moves `count` lines, starting at `index_map`, into a new sub proof
+    /// in their place. Returns the path of the new sub proof.
+    pub fn wrap(&mut self, index_map: &[usize], count: usize) -> Option<Vec<usize>> {
+        let (lines, i) = self.parent_mut(index_map)?;
+        if count == 0 || i + count > lines.len() {
+            return None;
+        }
+        let inner = lines.drain(i..i + count).collect::<Vec<_>>();
+        lines.insert(i, Line::Sub(inner.into()));
+        Some(index_map.to_vec())
+    }
+
+    /// Replaces the sub proof at `index_map` with its lines. Returns the path
+    /// of what was its first line.
+    pub fn unwrap(&mut self, index_map: &[usize]) -> Option<Vec<usize>> {
+        let (lines, i) = self.parent_mut(index_map)?;
+        let Some(Line::Sub(_)) = lines.get(i) else {
+            return None;
+        };
+        let Line::Sub(mut inner) = lines.remove(i) else {
+            unreachable!()
+        };
+        lines.splice(i..i, inner.lines_mut().drain(..));
+        Some(index_map.to_vec())
+    }
+
+    /// Inserts a copy of the line or sub proof after it.
+    pub fn duplicate(&mut self, index_map: &[usize]) -> Option<Vec<usize>> {
+        let (lines, i) = self.parent_mut(index_map)?;
+        let line = lines.get(i)?.clone();
+        lines.insert(i + 1, line);
+        Some(with_last(index_map, i + 1))
+    }
+
+    /// Splits the sub proof containing `index_map` in two, so that the line
+    /// begins the second one. Returns the path of the second sub proof.
+    pub fn split(&mut self, index_map: &[usize]) -> Option<Vec<usize>> {
+        let (i, parent) = index_map.split_last()?;
+        let (lines, j) = self.parent_mut(parent)?;
+        let Some(Line::Sub(s)) = lines.get_mut(j) else {
+            return None;
+        };
+        if *i == 0 || *i >= s.0.len() {
+            return None;
+        }
+        let tail = s.lines_mut().split_off(*i);
+        lines.insert(j + 1, Line::Sub(tail.into()));
+        Some(with_last(parent, j + 1))
+    }
+
+    /// Appends the lines of the sub proof after the one at `index_map` to it,
+    /// removing the second one. Returns the path of the first line that was
+    /// moved.
+    pub fn merge(&mut self, index_map: &[usize]) -> Option<Vec<usize>> {
+        let (lines, i) = self.parent_mut(index_map)?;
+        let (Some(Line::Sub(_)), Some(Line::Sub(_))) = (lines.get(i), lines.get(i + 1)) else {
+            return None;
+        };
+        let Line::Sub(mut next) = lines.remove(i + 1) else {
+            unreachable!()
+        };
+        let Line::Sub(s) = &mut lines[i] else {
+            unreachable!()
+        };
+        let mut res = index_map.to_vec();
+        res.push(s.0.len());
+        s.lines_mut().append(next.lines_mut());
+        Some(res)
+    }
+}
+
+/// A structural edit of a line, as bound to keys in the game and the
+/// terminal.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineEdit {
+    InsertAbove,
+    InsertBelow,
+    MoveUp,
+    MoveDown,
+    Duplicate,
+    /// Moves the line into a new sub proof.
+    Wrap,
+    /// Moves the lines of the sub proof containing the line out of it.
+    Unwrap,
+    /// Splits the sub proof containing the line, see [`SubProof::split`].
+    Split,
+    /// Merges the sub proof containing the line with the next one.
+    Merge,
+}
+
+/// What a [`LineEdit`] did to a proof.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Edited {
+    /// The line or sub proof to select after the edit.
+    pub selected: Vec<usize>,
+    /// Where the proof has to be verified again from. Lines may have moved in
+    /// or out of sub proofs, so this is the start of the enclosing one.
+    pub changed: Vec<usize>,
+}
+
+impl LineEdit {
+    /// Applies the edit to the line at `line`, or returns `None` and leaves
+    /// the proof unchanged if it can't be done there.
+    pub fn apply<T: Clone>(self, proof: &mut SubProof<T>, line: &[usize]) -> Option<Edited> {
+        let (&last, parent) = line.split_last()?;
+        let empty = || Line::Log(Logic::Empty.into(), None);
+        let selected = match self {
+            LineEdit::InsertAbove => proof.insert_before(line, empty()),
+            LineEdit::InsertBelow => proof.insert_after(line, empty()),
+            LineEdit::MoveUp => proof.move_up(line),
+            LineEdit::MoveDown => proof.move_down(line),
+            LineEdit::Duplicate => proof.duplicate(line),
+            LineEdit::Wrap => proof.wrap(line, 1).map(|mut r| {
+                r.push(0);
+                r
+            }),
+            LineEdit::Unwrap => proof.unwrap(parent).map(|mut r| {
+                *r.last_mut().unwrap() += last;
+                r
+            }),
+            LineEdit::Split => proof.split(line).map(|mut r| {
+                r.push(0);
+                r
+            }),
+            LineEdit::Merge => proof.merge(parent).map(|_| line.to_vec()),
+        }?;
+        let mut changed = line.min(selected.as_slice()).to_vec();
+        if changed.len() > 1 {
+            changed.pop();
+        }
+        Some(Edited { selected, changed })
+    }
+}
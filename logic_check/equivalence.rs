@@ -0,0 +1,364 @@
+use super::{FitchProof, Line, Logic, Ptr, SubProof};
+use std::{cmp::Ordering, ops::Range};
+
+impl<T: PartialEq> Logic<T> {
+    /// How often the variable occurs in the term.
+    fn occurrences(&self, var: &T) -> usize {
+        match self {
+            Logic::Variable(v) => usize::from(v == var),
+            Logic::And(a, b) | Logic::Implies(a, b) | Logic::Or(a, b) => {
+                a.occurrences(var) + b.occurrences(var)
+            }
+            Logic::Not(a) => a.occurrences(var),
+            Logic::Bottom | Logic::Empty => 0,
+        }
+    }
+
+    /// All variables of the term, in order of first appearance.
+    pub fn variables(&self) -> Vec<&T> {
+        let mut vars = Vec::new();
+        self.collect_variables(&mut vars);
+        vars
+    }
+
+    fn collect_variables<'a>(&'a self, vars: &mut Vec<&'a T>) {
+        match self {
+            Logic::Variable(v) => {
+                if !vars.contains(&v) {
+                    vars.push(v)
+                }
+            }
+            Logic::And(a, b) | Logic::Implies(a, b) | Logic::Or(a, b) => {
+                a.collect_variables(vars);
+                b.collect_variables(vars);
+            }
+            Logic::Not(a) => a.collect_variables(vars),
+            Logic::Bottom | Logic::Empty => {}
+        }
+    }
+
+    /// Evaluates the term under an assignment of the variables.
+    /// Returns `None` if the term contains an `Empty` hole.
+    pub fn evaluate(&self, assignment: &impl Fn(&T) -> bool) -> Option<bool> {
+        Some(match self {
+            Logic::Variable(v) => assignment(v),
+            Logic::And(a, b) => a.evaluate(assignment)? & b.evaluate(assignment)?,
+            Logic::Implies(a, b) => !a.evaluate(assignment)? | b.evaluate(assignment)?,
+            Logic::Not(a) => !a.evaluate(assignment)?,
+            Logic::Or(a, b) => a.evaluate(assignment)? | b.evaluate(assignment)?,
+            Logic::Bottom => false,
+            Logic::Empty => return None,
+        })
+    }
+
+    /// Checks if two terms are logically equivalent, i.e. have the same
+    /// truth value under every assignment. This builds a full truth table,
+    /// so it is exponential in the number of variables.
+    /// Terms containing `Empty` are never equivalent to anything, and neither
+    /// are terms with more variables than a truth table can have, see
+    /// [`MAX_VARIABLES`].
+    pub fn equivalent(&self, other: &Self) -> bool {
+        let mut vars = self.variables();
+        for v in other.variables() {
+            if !vars.contains(&v) {
+                vars.push(v);
+            }
+        }
+
+        let Some(rows) = rows(vars.len()) else {
+            return false;
+        };
+        rows.into_iter().all(|row| {
+            let assignment = |t: &T| {
+                let i = vars.iter().position(|v| *v == t).unwrap_or_default();
+                row & (1 << i) != 0
+            };
+            match (self.evaluate(&assignment), other.evaluate(&assignment)) {
+                (Some(a), Some(b)) => a == b,
+                _ => false,
+            }
+        })
+    }
+}
+
+/// The most variables a truth table can have, with a row for every
+/// assignment as the bits of a `u64`.
+pub const MAX_VARIABLES: usize = 63;
+
+/// The rows of the truth table of `variables` variables, or `None` if there
+/// are more than [`MAX_VARIABLES`].
+fn rows(variables: usize) -> Option<Range<u64>> {
+    let rows = 1u64.checked_shl(u32::try_from(variables).ok()?)?;
+    Some(0..rows)
+}
+
+/// Checks if the conclusion is true under every assignment that makes all
+/// the premises true, using a truth table like [`Logic::equivalent`].
+/// Puzzles with more than [`MAX_VARIABLES`] variables never entail anything.
+pub fn entails<T: PartialEq>(premises: &[Logic<T>], conclusion: &Logic<T>) -> bool {
+    let mut vars = conclusion.variables();
+    for p in premises {
+        p.collect_variables(&mut vars);
+    }
+
+    let Some(rows) = rows(vars.len()) else {
+        return false;
+    };
+    rows.into_iter().all(|row| {
+        let assignment = |t: &T| {
+            let i = vars.iter().position(|v| *v == t).unwrap_or_default();
+            row & (1 << i) != 0
+        };
+        let premises = premises
+            .iter()
+            .map(|p| p.evaluate(&assignment))
+            .collect::<Option<Vec<_>>>();
+        match (premises, conclusion.evaluate(&assignment)) {
+            (Some(p), Some(c)) => c || p.contains(&false),
+            _ => false,
+        }
+    })
+}
+
+impl<T> Logic<T> {
+    /// The same term with every variable replaced by `f` of it.
+    pub fn rename<'a, U>(&'a self, f: &mut impl FnMut(&'a T) -> U) -> Logic<U> {
+        match self {
+            Logic::Variable(v) => Logic::Variable(f(v)),
+            Logic::And(a, b) => Logic::And(a.rename(f).into(), b.rename(f).into()),
+            Logic::Implies(a, b) => Logic::Implies(a.rename(f).into(), b.rename(f).into()),
+            Logic::Not(a) => Logic::Not(a.rename(f).into()),
+            Logic::Or(a, b) => Logic::Or(a.rename(f).into(), b.rename(f).into()),
+            Logic::Bottom => Logic::Bottom,
+            Logic::Empty => Logic::Empty,
+        }
+    }
+}
+impl<T> Line<T> {
+    /// The same line with every variable replaced by `f` of it.
+    pub fn rename<'a, U>(&'a self, f: &mut impl FnMut(&'a T) -> U) -> Line<U> {
+        match self {
+            Line::Log(l, i) => Line::Log(l.rename(f).into(), i.clone()),
+            Line::Sub(s) => Line::Sub(s.rename(f)),
+        }
+    }
+}
+impl<T> SubProof<T> {
+    /// The same proof with every variable replaced by `f` of it.
+    pub fn rename<'a, U>(&'a self, f: &mut impl FnMut(&'a T) -> U) -> SubProof<U> {
+        SubProof::new(self.0.iter().map(|l| l.rename(f)).collect())
+    }
+}
+impl<T> FitchProof<T> {
+    /// The same puzzle and proof with every variable replaced by `f` of it.
+    pub fn rename<'a, U>(&'a self, f: &mut impl FnMut(&'a T) -> U) -> FitchProof<U> {
+        FitchProof {
+            proof: self.proof.rename(f),
+            prepositions: self.prepositions.iter().map(|p| p.rename(f)).collect(),
+            result: self.result.rename(f).into(),
+        }
+    }
+}
+impl<T: Clone> Logic<T> {
+    /// Orders the operands of `∧` and `∨` according to `cmp`.
+    fn sort_commutative(&mut self, cmp: fn(&Logic<T>, &Logic<T>) -> Ordering) {
+        match self {
+            Logic::And(a, b) | Logic::Or(a, b) => {
+                Ptr::make_mut(a).sort_commutative(cmp);
+                Ptr::make_mut(b).sort_commutative(cmp);
+                if cmp(a, b) == Ordering::Greater {
+                    std::mem::swap(a, b);
+                }
+            }
+            Logic::Implies(a, b) => {
+                Ptr::make_mut(a).sort_commutative(cmp);
+                Ptr::make_mut(b).sort_commutative(cmp);
+            }
+            Logic::Not(a) => Ptr::make_mut(a).sort_commutative(cmp),
+            Logic::Variable(_) | Logic::Bottom | Logic::Empty => {}
+        }
+    }
+}
+
+/// Renames all variables to `0, 1, 2, ...` in order of first appearance,
+/// going through the premises and then the result.
+fn rename_by_appearance<T: PartialEq>(
+    prepositions: &[Logic<T>],
+    result: &Logic<T>,
+) -> (Vec<Logic<usize>>, Logic<usize>) {
+    let mut seen = Vec::new();
+    for p in prepositions.iter().chain([result]) {
+        p.collect_variables(&mut seen);
+    }
+    let mut name = |v: &T| seen.iter().position(|s| *s == v).unwrap_or_default();
+    let prepositions = prepositions.iter().map(|p| p.rename(&mut name)).collect();
+    let result = result.rename(&mut name);
+    (prepositions, result)
+}
+
+fn sort_puzzle<T: Clone>(
+    prepositions: &mut [Logic<T>],
+    result: &mut Logic<T>,
+    cmp: fn(&Logic<T>, &Logic<T>) -> Ordering,
+) {
+    prepositions
+        .iter_mut()
+        .chain([&mut *result])
+        .for_each(|p| p.sort_commutative(cmp));
+    prepositions.sort_by(cmp);
+}
+
+/// Sorts the puzzle by the names of the variables, renaming them in between,
+/// until it settles.
+fn settle(
+    mut prepositions: Vec<Logic<usize>>,
+    mut result: Logic<usize>,
+) -> (Vec<Logic<usize>>, Logic<usize>) {
+    let limit = result.variables().len() + prepositions.len() + 2;
+    for _ in 0..limit {
+        sort_puzzle(&mut prepositions, &mut result, Ord::cmp);
+        let (p, r) = rename_by_appearance(&prepositions, &result);
+        if p == prepositions && r == result {
+            break;
+        }
+        (prepositions, result) = (p, r);
+    }
+    (prepositions, result)
+}
+
+/// What a variable is known by while its name doesn't count yet: in how
+/// many premises it occurs, how often it occurs in them, and how often in the
+/// result.
+type Colour = (usize, usize, usize);
+
+/// Compares two terms by their shapes and the colours of their variables,
+/// ignoring the names.
+fn cmp_colours<T>(a: &Logic<(Colour, T)>, b: &Logic<(Colour, T)>) -> Ordering {
+    let colours = |l: &Logic<(Colour, T)>| l.rename(&mut |(c, _)| *c);
+    colours(a).cmp(&colours(b))
+}
+
+/// The most orders of premises [`FitchProof::canonical`] tries.
+const MAX_ORDERS: usize = 720;
+
+/// Every order of `items` that only reorders runs of items that `cmp` finds
+/// equal, or just `items` if there are more than [`MAX_ORDERS`] of them.
+fn orders_of_ties<U: Clone>(items: &[U], cmp: impl Fn(&U, &U) -> Ordering) -> Vec<Vec<U>> {
+    fn permute<U: Clone>(items: &mut [U], k: usize, out: &mut Vec<Vec<U>>) {
+        if k == items.len() {
+            out.push(items.to_vec());
+        }
+        for i in k..items.len() {
+            items.swap(k, i);
+            permute(items, k + 1, out);
+            items.swap(k, i);
+        }
+    }
+
+    let runs = items
+        .chunk_by(|a, b| cmp(a, b) == Ordering::Equal)
+        .collect::<Vec<_>>();
+    let count = runs.iter().try_fold(1usize, |count, run| {
+        (1..=run.len()).try_fold(count, |count, n| count.checked_mul(n))
+    });
+    if count.is_none_or(|count| count > MAX_ORDERS) {
+        return vec![items.to_vec()];
+    }
+    let mut orders = vec![Vec::new()];
+    for run in runs {
+        let mut runs = Vec::new();
+        permute(&mut run.to_vec(), 0, &mut runs);
+        orders = orders
+            .iter()
+            .flat_map(|o| runs.iter().map(move |r| [o.as_slice(), r].concat()))
+            .collect();
+    }
+    orders
+}
+
+impl<T: PartialEq> FitchProof<T> {
+    /// The canonical form of the puzzle (the premises and the result).
+    ///
+    /// Variables are renamed in order of first appearance and the operands of
+    /// `∧`/`∨`, as well as the premises, are sorted. Two puzzles with the same
+    /// canonical form only differ in variable names and operand or premise
+    /// order. The proof itself is dropped.
+    ///
+    /// Premises are first told apart by how their variables occur in the
+    /// puzzle. The ones that can't be are tried in every order, unless there
+    /// are more than [`MAX_ORDERS`], in which case their order is kept and
+    /// the same puzzle may get different canonical forms.
+    pub fn canonical(&self) -> FitchProof<usize> {
+        let mut vars = self.result.variables();
+        for p in &self.prepositions {
+            p.collect_variables(&mut vars);
+        }
+        let colour = |v: &T| -> Colour {
+            let mut c = (0, 0, self.result.occurrences(v));
+            for p in &self.prepositions {
+                let n = p.occurrences(v);
+                c.0 += usize::from(n > 0);
+                c.1 += n;
+            }
+            c
+        };
+        let colours = vars.iter().map(|v| colour(v)).collect::<Vec<_>>();
+        let mut colour = |v| {
+            let i = vars.iter().position(|x| *x == v).unwrap_or_default();
+            (colours[i], v)
+        };
+
+        // First sort without looking at the names, so that the order of
+        // first appearance does not depend on the original names.
+        let mut prepositions = self
+            .prepositions
+            .iter()
+            .map(|p| p.rename(&mut colour))
+            .collect::<Vec<_>>();
+        let mut result = self.result.rename(&mut colour);
+        sort_puzzle(&mut prepositions, &mut result, cmp_colours);
+
+        // The names are all that tell the remaining ties apart, so their
+        // order is still arbitrary. Take the smallest outcome of them.
+        let result = result.rename(&mut |(_, v)| *v);
+        let (prepositions, result) = orders_of_ties(&prepositions, cmp_colours)
+            .into_iter()
+            .map(|order| {
+                let order = order
+                    .iter()
+                    .map(|p| p.rename(&mut |(_, v)| *v))
+                    .collect::<Vec<_>>();
+                let (prepositions, result) = rename_by_appearance(&order, &result);
+                settle(prepositions, result)
+            })
+            .min()
+            .expect("there is always at least one order");
+
+        FitchProof {
+            proof: SubProof::default(),
+            prepositions,
+            result: result.into(),
+        }
+    }
+
+    /// Checks if two puzzles are the same, up to variable names and the
+    /// order of commutative operands and premises.
+    pub fn same_puzzle<U: PartialEq>(&self, other: &FitchProof<U>) -> bool {
+        self.canonical() == other.canonical()
+    }
+}
+
+/// Finds all pairs of puzzles that are the same according to
+/// [`FitchProof::same_puzzle`]. The first index is always the smaller one.
+pub fn find_duplicates<T: PartialEq>(puzzles: &[FitchProof<T>]) -> Vec<(usize, usize)> {
+    let canonical = puzzles.iter().map(|p| p.canonical()).collect::<Vec<_>>();
+    let mut duplicates = Vec::new();
+    for (i, a) in canonical.iter().enumerate() {
+        for (j, b) in canonical.iter().enumerate().skip(i + 1) {
+            if a == b {
+                duplicates.push((i, j));
+            }
+        }
+    }
+    duplicates
+}
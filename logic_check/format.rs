@@ -0,0 +1,292 @@
+use super::{FitchProof, Line, Logic, SubProof};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::{collections::BTreeMap, fmt::Display};
+
+/// The newest version of the `.logiko.json` format.
+pub const VERSION: u32 = 1;
+
+/// The file extension used for proof files and puzzle packs.
+pub const EXTENSION: &str = ".logiko.json";
+
+/// A `.logiko.json` file, containing either a single puzzle (optionally
+/// with a proof) or a pack of puzzles.
+///
+/// The format has its own types for terms and proofs, so that it doesn't
+/// change when [`Logic`] or [`Line`] does. Justifications are not stored,
+/// they are recomputed when the proof is verified. Older files, including
+/// the plain serde output of [`FitchProof`] used before the format was
+/// versioned, are migrated when loaded. The JSON Schema can be found in
+/// `public/logiko.schema.json`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ProofFile {
+    pub version: u32,
+    #[serde(flatten)]
+    pub document: Document,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Document {
+    Proof(Puzzle),
+    Pack {
+        #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+        metadata: Metadata,
+        puzzles: Vec<Puzzle>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Puzzle {
+    #[serde(default, skip_serializing_if = "Metadata::is_empty")]
+    pub metadata: Metadata,
+    pub premises: Vec<Formula>,
+    pub result: Formula,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof: Option<Vec<Step>>,
+}
+
+/// Information about a puzzle or proof that the game doesn't need.
+/// Unknown fields are kept, so files can be round-tripped by older versions.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// When the file was created, as an RFC 3339 timestamp.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<String>,
+    #[serde(flatten)]
+    pub extra: BTreeMap<String, Value>,
+}
+impl Metadata {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Formula {
+    Var(String),
+    Not(Box<Formula>),
+    And(Box<Formula>, Box<Formula>),
+    Or(Box<Formula>, Box<Formula>),
+    Implies(Box<Formula>, Box<Formula>),
+    Bottom,
+    Empty,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Step {
+    Line(Formula),
+    Sub(Vec<Step>),
+}
+
+impl ProofFile {
+    pub fn new(document: Document) -> Self {
+        Self {
+            version: VERSION,
+            document,
+        }
+    }
+
+    /// Reads a file of any supported version.
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        let value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+        let value = migrate(value)?;
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn puzzles(&self) -> &[Puzzle] {
+        match &self.document {
+            Document::Proof(p) => std::slice::from_ref(p),
+            Document::Pack { puzzles, .. } => puzzles,
+        }
+    }
+}
+
+impl Puzzle {
+    pub fn new<T: Display>(proof: &FitchProof<T>, with_proof: bool) -> Self {
+        Self {
+            metadata: Metadata::default(),
+            premises: proof.prepositions.iter().map(Formula::from).collect(),
+            result: (&*proof.result).into(),
+            proof: with_proof.then(|| steps(&proof.proof)),
+        }
+    }
+
+    /// Converts the puzzle to a proof. Puzzles without a proof get an empty one.
+    pub fn to_proof(&self) -> FitchProof<String> {
+        FitchProof {
+            proof: self.proof.as_deref().map(sub_proof).unwrap_or_default(),
+            prepositions: self.premises.iter().map(Logic::from).collect(),
+            result: Logic::from(&self.result).into(),
+        }
+    }
+}
+
+fn steps<T: Display>(proof: &SubProof<T>) -> Vec<Step> {
+    proof
+        .0
+        .iter()
+        .map(|l| match l {
+            Line::Sub(s) => Step::Sub(steps(s)),
+            Line::Log(l, _) => Step::Line((&**l).into()),
+        })
+        .collect()
+}
+
+fn sub_proof(steps: &[Step]) -> SubProof<String> {
+    SubProof::new(
+        steps
+            .iter()
+            .map(|s| match s {
+                Step::Line(f) => Line::Log(Logic::from(f).into(), None),
+                Step::Sub(s) => Line::Sub(sub_proof(s)),
+            })
+            .collect(),
+    )
+}
+
+impl<T: Display> From<&Logic<T>> for Formula {
+    fn from(value: &Logic<T>) -> Self {
+        let f = |l: &Logic<T>| Box::new(Formula::from(l));
+        match value {
+            Logic::Variable(v) => Formula::Var(v.to_string()),
+            Logic::And(a, b) => Formula::And(f(a), f(b)),
+            Logic::Implies(a, b) => Formula::Implies(f(a), f(b)),
+            Logic::Not(a) => Formula::Not(f(a)),
+            Logic::Or(a, b) => Formula::Or(f(a), f(b)),
+            Logic::Bottom => Formula::Bottom,
+            Logic::Empty => Formula::Empty,
+        }
+    }
+}
+impl From<&Formula> for Logic<String> {
+    fn from(value: &Formula) -> Self {
+        let f = |l: &Formula| Logic::from(l).into();
+        match value {
+            Formula::Var(v) => Logic::Variable(v.clone()),
+            Formula::Not(a) => Logic::Not(f(a)),
+            Formula::And(a, b) => Logic::And(f(a), f(b)),
+            Formula::Or(a, b) => Logic::Or(f(a), f(b)),
+            Formula::Implies(a, b) => Logic::Implies(f(a), f(b)),
+            Formula::Bottom => Logic::Bottom,
+            Formula::Empty => Logic::Empty,
+        }
+    }
+}
+
+type Migration = fn(Value) -> Result<Value, String>;
+
+/// Migrations from each version to the next, `MIGRATIONS[n]` takes a file
+/// of version `n` to version `n + 1`.
+const MIGRATIONS: &[Migration] = &[v0_to_v1];
+
+fn migrate(mut value: Value) -> Result<Value, String> {
+    let mut version = match &value {
+        Value::Object(o) => match o.get("version") {
+            Some(v) => v
+                .as_u64()
+                .and_then(|n| u32::try_from(n).ok())
+                .ok_or_else(|| format!("invalid version: {v}"))?,
+            None => 0,
+        },
+        // Puzzle packs used to be plain lists
+        Value::Array(_) => 0,
+        _ => return Err("expected an object or a list".to_string()),
+    };
+    if version > VERSION {
+        return Err(format!(
+            "file has version {version}, but only versions up to {VERSION} are supported"
+        ));
+    }
+    while version < VERSION {
+        value = MIGRATIONS[version as usize](value)?;
+        version += 1;
+    }
+    Ok(value)
+}
+
+/// Version 0 is the serde output of `FitchProof` (or a list of them),
+/// which used the names of the Rust types and stored the justifications.
+fn v0_to_v1(value: Value) -> Result<Value, String> {
+    fn logic(value: &Value) -> Result<Value, String> {
+        let pair = |v: &Value| match v.as_array().map(|v| &v[..]) {
+            Some([a, b]) => Ok(json!([logic(a)?, logic(b)?])),
+            _ => Err(format!("expected two terms: {v}")),
+        };
+        Ok(match value {
+            Value::String(s) if s == "Bottom" => json!("bottom"),
+            Value::String(s) if s == "Empty" => json!("empty"),
+            Value::Object(o) if o.len() == 1 => {
+                let (k, v) = o.iter().next().unwrap();
+                match k.as_str() {
+                    "Variable" => json!({ "var": v }),
+                    "Not" => json!({ "not": logic(v)? }),
+                    "And" => json!({ "and": pair(v)? }),
+                    "Or" => json!({ "or": pair(v)? }),
+                    "Implies" => json!({ "implies": pair(v)? }),
+                    _ => return Err(format!("unknown term: {k}")),
+                }
+            }
+            _ => return Err(format!("invalid term: {value}")),
+        })
+    }
+    fn lines(value: &Value) -> Result<Value, String> {
+        let list = value.as_array().ok_or("expected a list of lines")?;
+        list.iter()
+            .map(|l| match l.as_object().and_then(|o| o.iter().next()) {
+                Some((k, v)) if k == "Sub" => Ok(json!({ "sub": lines(v)? })),
+                Some((k, v)) if k == "Log" => match v.as_array().and_then(|v| v.first()) {
+                    Some(t) => Ok(json!({ "line": logic(t)? })),
+                    None => Err(format!("invalid line: {l}")),
+                },
+                _ => Err(format!("invalid line: {l}")),
+            })
+            .collect()
+    }
+    fn puzzle(value: &Value) -> Result<Value, String> {
+        let o = value.as_object().ok_or("expected a proof")?;
+        let premises = o
+            .get("prepositions")
+            .and_then(|p| p.as_array())
+            .ok_or("missing prepositions")?;
+        let mut res = Map::new();
+        res.insert(
+            "premises".to_string(),
+            premises.iter().map(logic).collect::<Result<_, _>>()?,
+        );
+        res.insert(
+            "result".to_string(),
+            logic(o.get("result").ok_or("missing result")?)?,
+        );
+        if let Some(proof) = o.get("proof") {
+            res.insert("proof".to_string(), lines(proof)?);
+        }
+        Ok(Value::Object(res))
+    }
+
+    Ok(match &value {
+        Value::Array(puzzles) => json!({
+            "version": 1,
+            "kind": "pack",
+            "puzzles": puzzles.iter().map(puzzle).collect::<Result<Vec<_>, _>>()?,
+        }),
+        _ => {
+            let mut res = puzzle(&value)?;
+            res["version"] = json!(1);
+            res["kind"] = json!("proof");
+            res
+        }
+    })
+}
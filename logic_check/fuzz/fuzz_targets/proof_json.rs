@@ -0,0 +1,35 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use logic_check::{format::ProofFile, FitchProof, Verifier};
+
+/// Everything that is done with a proof after it has been loaded.
+fn exercise(mut proof: FitchProof<String>) {
+    let _ = proof.verify();
+    let _ = proof.to_string();
+    let _ = proof.latex();
+    let _ = proof.stats();
+    let _ = proof.canonical();
+
+    let mut verifier = Verifier::new();
+    let _ = verifier.verify(&mut proof);
+    if let Some(i) = proof.proof.0.len().checked_sub(1) {
+        proof.proof.remove_line(&[i]);
+        let _ = verifier.reverify(&mut proof, &[i]);
+    }
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Ok(json) = std::str::from_utf8(data) else {
+        return;
+    };
+    if let Ok(proof) = serde_json::from_str::<FitchProof<String>>(json) {
+        exercise(proof);
+    }
+    if let Ok(file) = ProofFile::from_json(json) {
+        let _ = file.to_json();
+        for puzzle in file.puzzles() {
+            exercise(puzzle.to_proof());
+        }
+    }
+});
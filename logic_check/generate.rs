@@ -0,0 +1,225 @@
+use super::{FitchProof, Line, Logic, Ptr, SubProof};
+
+/// A small seedable random number generator (SplitMix64), so that everything
+/// generated here can be reproduced from a seed.
+#[derive(Debug, Clone)]
+pub struct Rng(u64);
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+
+    /// A number in `0..n`, `n` must not be zero.
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    /// True with a probability of `1 / n`.
+    pub fn one_in(&mut self, n: usize) -> bool {
+        self.below(n) == 0
+    }
+
+    pub fn pick<'a, X>(&mut self, items: &'a [X]) -> &'a X {
+        &items[self.below(items.len())]
+    }
+}
+
+/// A random term over the given variables, at most `depth` operators deep.
+/// Never contains `Empty`.
+pub fn logic<T: Clone>(rng: &mut Rng, variables: &[T], depth: usize) -> Logic<T> {
+    if depth == 0 || rng.one_in(3) {
+        return if rng.one_in(10) {
+            Logic::Bottom
+        } else {
+            Logic::Variable(rng.pick(variables).clone())
+        };
+    }
+    let op = rng.below(4);
+    let mut sub = || logic(rng, variables, depth - 1).into();
+    match op {
+        0 => Logic::And(sub(), sub()),
+        1 => Logic::Or(sub(), sub()),
+        2 => Logic::Implies(sub(), sub()),
+        _ => Logic::Not(sub()),
+    }
+}
+
+/// Builds proofs by applying random rules to what is in scope, so every
+/// proof it makes is one that [`FitchProof::verify`] should accept.
+struct Builder<'a, T> {
+    rng: &'a mut Rng,
+    variables: &'a [T],
+    /// What has been proven in each scope, the innermost last.
+    scopes: Vec<Vec<Logic<T>>>,
+    budget: usize,
+}
+impl<T: Clone + PartialEq> Builder<'_, T> {
+    fn facts(&self) -> Vec<Logic<T>> {
+        self.scopes.iter().flatten().cloned().collect()
+    }
+
+    fn random(&mut self) -> Logic<T> {
+        logic(self.rng, self.variables, 2)
+    }
+
+    fn add(&mut self, lines: &mut Vec<Line<T>>, l: Logic<T>) {
+        self.budget = self.budget.saturating_sub(1);
+        self.scopes.last_mut().unwrap().push(l.clone());
+        lines.push(Line::Log(l.into(), None));
+    }
+
+    /// A new line that follows from one or two facts in scope.
+    fn derive(&mut self) -> Option<Logic<T>> {
+        let facts = self.facts();
+        if facts.is_empty() {
+            return None;
+        }
+        let a = self.rng.pick(&facts).clone();
+        let b = self.rng.pick(&facts).clone();
+        let find = |f: &dyn Fn(&Logic<T>) -> bool| facts.iter().find(|l| f(l)).cloned();
+        let l = match self.rng.below(9) {
+            0 => Logic::And(a.into(), b.into()),
+            1 => match a {
+                Logic::And(l, r) => Ptr::unwrap_or_clone(if self.rng.one_in(2) { l } else { r }),
+                _ => return None,
+            },
+            2 => {
+                let other = self.random();
+                if self.rng.one_in(2) {
+                    Logic::Or(a.into(), other.into())
+                } else {
+                    Logic::Or(other.into(), a.into())
+                }
+            }
+            3 => match a {
+                Logic::Implies(l, r) if facts.contains(&l) => Ptr::unwrap_or_clone(r),
+                _ => return None,
+            },
+            4 => {
+                find(&|l| facts.contains(&Logic::Not(l.clone().into())))?;
+                Logic::Bottom
+            }
+            5 => Logic::Not(Logic::Not(a.into()).into()),
+            6 => match a {
+                Logic::Not(l) => match Ptr::unwrap_or_clone(l) {
+                    Logic::Not(l) => Ptr::unwrap_or_clone(l),
+                    _ => return None,
+                },
+                _ => return None,
+            },
+            7 => {
+                find(&|l| *l == Logic::Bottom)?;
+                self.random()
+            }
+            _ => {
+                let other = self.random();
+                Logic::Or(Logic::Not(other.clone().into()).into(), other.into())
+            }
+        };
+        Some(l)
+    }
+
+    /// A sub proof starting with the assumption, with its last line.
+    fn sub_proof(&mut self, assumption: Logic<T>, depth: usize) -> (Line<T>, Logic<T>) {
+        self.scopes.push(Vec::new());
+        let mut lines = Vec::new();
+        self.add(&mut lines, assumption);
+        let inner = self.budget.min(1 + self.rng.below(4));
+        let rest = self.budget - inner;
+        self.budget = inner;
+        self.lines(&mut lines, depth + 1);
+        self.budget += rest;
+        self.scopes.pop();
+        let Some(Line::Log(last, _)) = lines.last() else {
+            unreachable!()
+        };
+        let last = (**last).clone();
+        (Line::Sub(SubProof::new(lines)), last)
+    }
+
+    fn lines(&mut self, lines: &mut Vec<Line<T>>, depth: usize) {
+        while self.budget > 0 {
+            if depth < 3 && self.budget >= 3 && self.rng.one_in(4) {
+                let ors = self
+                    .facts()
+                    .into_iter()
+                    .filter_map(|l| match l {
+                        Logic::Or(a, b) => Some((Ptr::unwrap_or_clone(a), Ptr::unwrap_or_clone(b))),
+                        _ => None,
+                    })
+                    .collect::<Vec<_>>();
+                if !ors.is_empty() && self.rng.one_in(2) {
+                    let (a, b) = self.rng.pick(&ors).clone();
+                    // or elimination, swapping the sides
+                    let swapped = Logic::Or(b.clone().into(), a.clone().into());
+                    for assumption in [a, b] {
+                        self.scopes.push(Vec::new());
+                        let mut sub = Vec::new();
+                        self.add(&mut sub, assumption);
+                        self.add(&mut sub, swapped.clone());
+                        self.scopes.pop();
+                        lines.push(Line::Sub(SubProof::new(sub)));
+                    }
+                    self.add(lines, swapped);
+                    continue;
+                }
+
+                let assumption = self.random();
+                let (sub, last) = self.sub_proof(assumption.clone(), depth);
+                lines.push(sub);
+                let l = match (last, assumption) {
+                    (Logic::Bottom, Logic::Not(a)) if self.rng.one_in(2) => Ptr::unwrap_or_clone(a),
+                    (Logic::Bottom, a) => Logic::Not(a.into()),
+                    (last, a) => Logic::Implies(a.into(), last.into()),
+                };
+                self.add(lines, l);
+            } else if let Some(l) = self.derive() {
+                self.add(lines, l);
+            } else if let Some(l) = self.facts().pop() {
+                // copy
+                self.add(lines, l);
+            } else {
+                let other = self.random();
+                let l = Logic::Or(Logic::Not(other.clone().into()).into(), other.into());
+                self.add(lines, l);
+            }
+        }
+    }
+}
+
+/// A random proof with about `lines` lines, which proves its result from
+/// `premises` random premises. The result is the last line of the proof.
+pub fn proof<T: Clone + PartialEq>(
+    rng: &mut Rng,
+    variables: &[T],
+    premises: usize,
+    lines: usize,
+) -> FitchProof<T> {
+    let prepositions = (0..premises)
+        .map(|_| logic(rng, variables, 2))
+        .collect::<Vec<_>>();
+    let mut builder = Builder {
+        rng,
+        variables,
+        scopes: vec![prepositions.clone()],
+        budget: lines.max(1),
+    };
+    let mut proof = Vec::new();
+    builder.lines(&mut proof, 0);
+    let Some(Line::Log(result, _)) = proof.last() else {
+        unreachable!()
+    };
+    FitchProof {
+        result: result.clone(),
+        proof: SubProof::new(proof),
+        prepositions,
+    }
+}
@@ -0,0 +1,75 @@
+//! A grid of squares showing the shape of a proof without giving it away,
+//! for sharing a result like Wordle does. Every line is a row, indented by
+//! one square per sub proof it is in, and ends with a square for the kind of
+//! rule it follows by.
+//!
+//! ```text
+//! 🟪
+//! ⬜🟦
+//! 🟩
+//! ```
+
+use super::{Instruction, Line, SubProof};
+
+/// The kinds of rules, which is all the grid shows of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Family {
+    Assumption,
+    Introduction,
+    Elimination,
+    /// `LEM` and `PBC`, which only hold classically.
+    Classical,
+    Copy,
+    Invalid,
+}
+impl Family {
+    pub fn of(instruction: &Instruction) -> Self {
+        use Instruction::*;
+        match instruction {
+            Assumption | Premise => Family::Assumption,
+            AndIntro(..) | OrIntroLeft(_) | OrIntroRight(_) | ImplIntro(_) | NotIntro(_)
+            | NotNotIntro(_) => Family::Introduction,
+            AndElimLeft(_) | AndElimRight(_) | OrElim(..) | ImplElim(..) | NotElim(..)
+            | NotNotElim(_) | BottomElim(_) => Family::Elimination,
+            Lem | Pbc(_) => Family::Classical,
+            Copy(_) => Family::Copy,
+            Invalid => Family::Invalid,
+        }
+    }
+
+    pub fn square(self) -> char {
+        match self {
+            Family::Assumption => '🟪',
+            Family::Introduction => '🟩',
+            Family::Elimination => '🟦',
+            Family::Classical => '🟥',
+            Family::Copy => '🟨',
+            Family::Invalid => '⬛',
+        }
+    }
+}
+
+/// The square for every level of sub proof a line is in.
+pub const INDENT: char = '⬜';
+
+/// The grid of a verified proof, one row per line. Lines that haven't been
+/// verified count as invalid.
+pub fn grid<T>(proof: &SubProof<T>) -> String {
+    fn rows<T>(proof: &SubProof<T>, depth: usize, out: &mut Vec<String>) {
+        for line in proof.0.iter() {
+            match line {
+                Line::Sub(s) => rows(s, depth + 1, out),
+                Line::Log(_, inst) => {
+                    let family = inst.as_ref().map_or(Family::Invalid, Family::of);
+                    let mut row = INDENT.to_string().repeat(depth);
+                    row.push(family.square());
+                    out.push(row);
+                }
+            }
+        }
+    }
+
+    let mut out = Vec::new();
+    rows(proof, 0, &mut out);
+    out.join("\n")
+}
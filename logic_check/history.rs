@@ -0,0 +1,241 @@
+use super::{Line, ProofPath, SubProof};
+use serde::{Deserialize, Serialize};
+
+/// A change to a proof: the lines `start..start + before.len()` of the sub
+/// proof at `at` were replaced by `after`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Patch<T> {
+    at: Vec<usize>,
+    start: usize,
+    before: Vec<Line<T>>,
+    after: Vec<Line<T>>,
+}
+impl<T: Clone> Patch<T> {
+    /// The smallest patch taking `a` to `b`, if they differ.
+    fn new(a: &[Line<T>], b: &[Line<T>], at: &mut Vec<usize>) -> Option<Self>
+    where
+        T: PartialEq,
+    {
+        let prefix = a.iter().zip(b).take_while(|(x, y)| x == y).count();
+        let suffix = a[prefix..]
+            .iter()
+            .rev()
+            .zip(b[prefix..].iter().rev())
+            .take_while(|(x, y)| x == y)
+            .count();
+        let (a, b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+        match (a, b) {
+            ([], []) => None,
+            ([Line::Sub(x)], [Line::Sub(y)]) => {
+                at.push(prefix);
+                Self::new(&x.0, &y.0, at)
+            }
+            _ => Some(Self {
+                at: at.clone(),
+                start: prefix,
+                before: a.to_vec(),
+                after: b.to_vec(),
+            }),
+        }
+    }
+
+    fn rename<'a, U>(&'a self, f: &mut impl FnMut(&'a T) -> U) -> Patch<U> {
+        Patch {
+            at: self.at.clone(),
+            start: self.start,
+            before: self.before.iter().map(|l| l.rename(f)).collect(),
+            after: self.after.iter().map(|l| l.rename(f)).collect(),
+        }
+    }
+
+    /// The path of the first line that changed.
+    fn edit(&self) -> Vec<usize> {
+        let mut res = self.at.clone();
+        res.push(self.start);
+        res
+    }
+
+    fn apply(&self, proof: &mut SubProof<T>, undo: bool) {
+        let (old, new) = if undo {
+            (&self.after, &self.before)
+        } else {
+            (&self.before, &self.after)
+        };
+        let sub_proof = if self.at.is_empty() {
+            proof
+        } else if let Some(Line::Sub(s)) = proof.line_at_mut(&self.at) {
+            s
+        } else {
+            return;
+        };
+        let end = (self.start + old.len()).min(sub_proof.0.len());
+        sub_proof
+            .lines_mut()
+            .splice(self.start..end, new.iter().cloned());
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct Entry<T> {
+    patch: Patch<T>,
+    selection: Option<ProofPath>,
+}
+
+/// Where the history moved to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Restored {
+    /// The first line that changed, see [`super::Verifier::reverify`].
+    pub edit: Vec<usize>,
+    /// What to select: the selection from before the edit when undoing,
+    /// and the changed line when redoing.
+    pub selection: Option<ProofPath>,
+}
+
+/// The edit history of a proof, with undo and redo.
+///
+/// Only the lines that changed in each edit are kept, so the history
+/// doesn't need a limit while playing, see [`History::recent`] for saving.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct History<T> {
+    entries: Vec<Entry<T>>,
+    /// The number of entries that are applied, the rest have been undone.
+    position: usize,
+    /// How many times an edit has been undone, see [`super::score`].
+    #[serde(default)]
+    undos: usize,
+}
+impl<T> Default for History<T> {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            position: 0,
+            undos: 0,
+        }
+    }
+}
+impl<T: Clone> History<T> {
+    /// The same history with every variable replaced by `f` of it.
+    pub fn rename<'a, U>(&'a self, f: &mut impl FnMut(&'a T) -> U) -> History<U> {
+        let entries = self.entries.iter().map(|e| Entry {
+            patch: e.patch.rename(f),
+            selection: e.selection.clone(),
+        });
+        History {
+            entries: entries.collect(),
+            position: self.position,
+            undos: self.undos,
+        }
+    }
+}
+impl<T: Clone + PartialEq> History<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The number of edits that can be undone.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// The number of edits, including the ones that have been undone.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// The same history with at most `max` edits. The oldest ones are
+    /// dropped first, so they can't be undone anymore, and then the ones
+    /// furthest from being redone.
+    pub fn recent(&self, max: usize) -> Self {
+        let dropped = self.entries.len().saturating_sub(max).min(self.position);
+        let mut entries = self.entries[dropped..].to_vec();
+        entries.truncate(max);
+        Self {
+            entries,
+            position: self.position - dropped,
+            undos: self.undos,
+        }
+    }
+
+    /// How many times [`History::undo`] has undone an edit.
+    pub fn undos(&self) -> usize {
+        self.undos
+    }
+
+    pub fn can_undo(&self) -> bool {
+        self.position > 0
+    }
+
+    pub fn can_redo(&self) -> bool {
+        self.position < self.entries.len()
+    }
+
+    /// Records an edit from `before` to `after`, where `selection` was
+    /// selected before the edit. Edits that were undone can't be redone
+    /// after this. Returns false if nothing changed.
+    pub fn record(
+        &mut self,
+        before: &SubProof<T>,
+        after: &SubProof<T>,
+        selection: Option<ProofPath>,
+    ) -> bool {
+        let Some(patch) = Patch::new(&before.0, &after.0, &mut Vec::new()) else {
+            return false;
+        };
+        self.entries.truncate(self.position);
+        self.entries.push(Entry { patch, selection });
+        self.position += 1;
+        true
+    }
+
+    pub fn undo(&mut self, proof: &mut SubProof<T>) -> Option<Restored> {
+        let restored = self.jump(proof, self.position.checked_sub(1)?)?;
+        self.undos += 1;
+        Some(restored)
+    }
+
+    pub fn redo(&mut self, proof: &mut SubProof<T>) -> Option<Restored> {
+        self.jump(proof, self.position + 1)
+    }
+
+    /// Undoes or redoes edits until `position` of them are applied.
+    pub fn jump(&mut self, proof: &mut SubProof<T>, position: usize) -> Option<Restored> {
+        if position > self.entries.len() || position == self.position {
+            return None;
+        }
+        let mut edit: Option<Vec<usize>> = None;
+        let mut selection = None;
+        while self.position != position {
+            let entry = if position < self.position {
+                self.position -= 1;
+                let entry = &self.entries[self.position];
+                entry.patch.apply(proof, true);
+                selection = entry.selection.clone();
+                entry
+            } else {
+                let entry = &self.entries[self.position];
+                self.position += 1;
+                entry.patch.apply(proof, false);
+                selection = entry
+                    .patch
+                    .after
+                    .first()
+                    .filter(|l| matches!(l, Line::Log(_, _)))
+                    .map(|_| ProofPath::new(entry.patch.edit()));
+                entry
+            };
+            let e = entry.patch.edit();
+            edit = Some(match edit {
+                Some(edit) => edit.min(e),
+                None => e,
+            });
+        }
+        Some(Restored {
+            edit: edit.unwrap(),
+            selection,
+        })
+    }
+}
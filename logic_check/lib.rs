@@ -1,17 +1,43 @@
 use std::{
     fmt::{Display, Write},
     ops::{AddAssign, RangeInclusive},
+    sync::Arc,
 };
 
-pub type Ptr<T> = Box<T>;
+/// Terms and sub proofs are shared between clones of a proof, and copied
+/// when they are changed, so cloning a proof is cheap.
+pub type Ptr<T> = Arc<T>;
 
 use serde::{Deserialize, Serialize};
 use Instruction::*;
 use Line::*;
 use Logic::*;
 
+mod arena;
+mod edit;
+mod equivalence;
+mod history;
+mod parse;
+mod path;
 mod verify;
 
+pub mod format;
+pub mod generate;
+pub mod grid;
+pub mod records;
+pub mod schedule;
+pub mod score;
+pub mod share;
+pub mod text;
+
+pub use arena::{Arena, Node, TermId};
+pub use edit::{Edited, LineEdit};
+pub use equivalence::{entails, find_duplicates, MAX_VARIABLES};
+pub use history::{History, Restored};
+pub use parse::{parse_sequent, ParseError};
+pub use path::ProofPath;
+pub use verify::Verifier;
+
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Instruction {
     Assumption,                                                  // Implemented
@@ -64,6 +90,62 @@ impl Instruction {
             Invalid => "invalid".to_string(),
         }
     }
+
+    pub fn typst(&self) -> String {
+        match self {
+            Assumption => "assumption".to_string(),
+            OrIntroLeft(i) => format!("$or_(i 1)$ {i}"),
+            OrIntroRight(i) => format!("$or_(i 2)$ {i}"),
+            OrElim(i, a, b) => format!(
+                "$or_e$ {i} {}-{} {}-{}",
+                a.start(),
+                a.end(),
+                b.start(),
+                b.end()
+            ),
+            NotElim(a, b) => format!("$not_e$ {a} {b}"),
+            NotIntro(i) => format!("$not_i$ {}-{}", i.start(), i.end()),
+            BottomElim(i) => format!("$bot_e$ {i}"),
+            ImplIntro(i) => format!("$->_i$ {}-{}", i.start(), i.end()),
+            ImplElim(a, b) => format!("$->_e$ {a} {b}"),
+            AndIntro(a, b) => format!("$and_i$ {a} {b}"),
+            AndElimLeft(i) => format!("$and_(e 1)$ {i}"),
+            AndElimRight(i) => format!("$and_(e 2)$ {i}"),
+            Pbc(i) => format!("PBC {}-{}", i.start(), i.end()),
+            Copy(i) => format!("copy {i}"),
+            NotNotIntro(i) => format!("$not not_i$ {i}"),
+            NotNotElim(i) => format!("$not not_e$ {i}"),
+            Lem => "LEM".to_string(),
+            Premise => "premise".to_string(),
+            Invalid => "invalid".to_string(),
+        }
+    }
+
+    /// The rule as printed by `Display`, without its line numbers, see
+    /// [`text::RULES`].
+    pub fn rule(&self) -> &'static str {
+        match self {
+            Assumption => "ass",
+            OrIntroLeft(_) => "∨l",
+            OrIntroRight(_) => "∨r",
+            OrElim(..) => "∨e",
+            AndIntro(..) => "∧i",
+            AndElimLeft(_) => "∧l",
+            AndElimRight(_) => "∧r",
+            NotElim(..) => "¬e",
+            NotNotElim(_) => "¬¬e",
+            NotIntro(_) => "¬i",
+            NotNotIntro(_) => "¬¬i",
+            BottomElim(_) => "⊥e",
+            ImplIntro(_) => "→i",
+            ImplElim(..) => "→e",
+            Pbc(_) => "PBC",
+            Copy(_) => "copy",
+            Invalid => "🛑",
+            Lem => "LEM",
+            Premise => "pre",
+        }
+    }
 }
 impl Display for Instruction {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -111,7 +193,7 @@ pub enum SelectType {
 }
 
 #[allow(clippy::derived_hash_with_manual_eq)]
-#[derive(Debug, Clone, Hash, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Hash, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum Logic<T> {
     Variable(T),
     And(Ptr<Logic<T>>, Ptr<Logic<T>>),
@@ -134,8 +216,22 @@ impl<T> Logic<T> {
         }
     }
 
-    pub fn get_mut(&mut self, index: usize) -> Option<&mut Self> {
+    pub fn get(&self, index: usize) -> Option<&Self> {
         match self {
+            And(a, b) | Implies(a, b) | Or(a, b) => match index {
+                0 => Some(a),
+                1 => Some(b),
+                _ => None,
+            },
+            Not(a) => Some(a),
+            Variable(_) | Bottom | Empty => None,
+        }
+    }
+}
+impl<T: Clone> Logic<T> {
+    /// A sub term, which is copied first if it is shared with another proof.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut Self> {
+        let child = match self {
             Variable(_) => None,
             And(a, _) if index == 0 => Some(a),
             And(_, a) if index == 1 => Some(a),
@@ -149,10 +245,15 @@ impl<T> Logic<T> {
             Or(_, _) => None,
             Bottom => None,
             Empty => None,
-        }
+        };
+        child.map(Ptr::make_mut)
     }
 
-    fn recurse<R>(&mut self, index_map: &[usize], term_func: fn(&mut Logic<T>) -> R) -> Option<R> {
+    fn recurse<R>(
+        &mut self,
+        index_map: &[usize],
+        term_func: impl FnOnce(&mut Logic<T>) -> R,
+    ) -> Option<R> {
         let Some(index) = index_map.first().copied() else {
             return Some(term_func(self));
         };
@@ -196,25 +297,61 @@ impl<T: Display> Logic<T> {
             format!("({res})")
         }
     }
+
+    /// The term in Typst math mode, where names longer than a letter are
+    /// quoted so that they aren't read as symbols.
+    pub fn typst(&self, outer: bool) -> String {
+        let res = match self {
+            Variable(v) => {
+                let v = v.to_string();
+                if v.chars().count() > 1 {
+                    format!("{v:?}")
+                } else {
+                    v
+                }
+            }
+            And(a, b) => format!("{} and {}", a.typst(false), b.typst(false)),
+            Implies(a, b) => format!("{} -> {}", a.typst(false), b.typst(false)),
+            Not(a) => format!("not {}", a.typst(false)),
+            Or(a, b) => format!("{} or {}", a.typst(false), b.typst(false)),
+            Bottom => "bot".to_string(),
+            Empty => "times".to_string(),
+        };
+        if outer || matches!(self, Variable(_) | Bottom) {
+            res
+        } else {
+            format!("({res})")
+        }
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
-pub struct SubProof<T>(pub Vec<Line<T>>);
+pub struct SubProof<T>(pub Ptr<Vec<Line<T>>>);
 impl<T> Default for SubProof<T> {
     fn default() -> Self {
-        Self(vec![Line::Log(Empty.into(), None)])
+        Self::new(vec![Line::Log(Empty.into(), None)])
     }
 }
-impl<T> SubProof<T> {
+impl<T> From<Vec<Line<T>>> for SubProof<T> {
+    fn from(value: Vec<Line<T>>) -> Self {
+        Self::new(value)
+    }
+}
+impl<T: Clone> SubProof<T> {
+    /// The lines, which are copied first if they are shared with another proof.
+    pub fn lines_mut(&mut self) -> &mut Vec<Line<T>> {
+        Ptr::make_mut(&mut self.0)
+    }
+
     pub fn make_sub_proof(&mut self, index_map: &[usize]) {
         match index_map {
             [i] => {
-                if let Some(c) = self.0.get_mut(*i) {
-                    *c = Sub(SubProof(vec![Log(Empty.into(), None)]))
+                if let Some(c) = self.lines_mut().get_mut(*i) {
+                    *c = Sub(SubProof::new(vec![Log(Empty.into(), None)]))
                 }
             }
             [i, xs @ ..] => {
-                if let Some(Sub(s)) = self.0.get_mut(*i) {
+                if let Some(Sub(s)) = self.lines_mut().get_mut(*i) {
                     s.make_sub_proof(xs);
                 }
             }
@@ -226,11 +363,11 @@ impl<T> SubProof<T> {
         match index_map {
             [i] => {
                 if *i < self.0.len() {
-                    self.0.remove(*i);
+                    self.lines_mut().remove(*i);
                 }
             }
             [i, xs @ ..] => {
-                if let Some(Sub(s)) = self.0.get_mut(*i) {
+                if let Some(Sub(s)) = self.lines_mut().get_mut(*i) {
                     s.remove_line(xs);
                 }
             }
@@ -238,6 +375,28 @@ impl<T> SubProof<T> {
         }
     }
 
+    pub fn recurse<R>(
+        &mut self,
+        index_map: &[usize],
+        sub_func: impl FnOnce(&mut SubProof<T>) -> R,
+        term_func: impl FnOnce(&mut Logic<T>) -> R,
+    ) -> Option<R> {
+        let Some(index) = index_map.first().copied() else {
+            return Some(sub_func(self));
+        };
+        let item = self.lines_mut().get_mut(index)?;
+
+        match item {
+            Line::Sub(s) => s.recurse(&index_map[1..], sub_func, term_func),
+            Line::Log(l, _) => Ptr::make_mut(l).recurse(&index_map[1..], term_func),
+        }
+    }
+}
+impl<T> SubProof<T> {
+    pub fn new(lines: Vec<Line<T>>) -> Self {
+        Self(lines.into())
+    }
+
     pub fn is_outer_term(&self, index_map: &[usize]) -> bool {
         match index_map {
             [i] => *i < self.0.len() && matches!(&self.0[*i], Line::Log(_, _)),
@@ -266,37 +425,21 @@ impl<T> SubProof<T> {
         self.len() == 0
     }
 
-    pub fn recurse<R>(
-        &mut self,
-        index_map: &[usize],
-        sub_func: fn(&mut SubProof<T>) -> R,
-        term_func: fn(&mut Logic<T>) -> R,
-    ) -> Option<R> {
-        let Some(index) = index_map.first().copied() else {
-            return Some(sub_func(self));
-        };
-        let item = self.0.get_mut(index)?;
-
-        match item {
-            Line::Sub(s) => s.recurse(&index_map[1..], sub_func, term_func),
-            Line::Log(l, _) => l.recurse(&index_map[1..], term_func),
-        }
-    }
-
     pub fn stats(&self) -> Stats {
         let mut s = Stats {
-            lines: self.0.len(),
+            lines: 0,
             terms: 0,
             sub_proofs: 0,
         };
 
-        for line in &self.0 {
+        for line in self.0.iter() {
             match line {
                 Sub(ns) => {
                     s.sub_proofs += 1;
                     s += ns.stats();
                 }
                 Log(t, _) => {
+                    s.lines += 1;
                     s.terms += t.size();
                 }
             }
@@ -305,10 +448,14 @@ impl<T> SubProof<T> {
         s
     }
 }
+/// The column the rules start at when a proof is displayed, unless the line
+/// is longer than that.
+pub const RULE_COLUMN: usize = 32;
+
 impl<T: Display> SubProof<T> {
     pub fn display(&self, index: &mut usize, depth: usize) -> String {
         let mut res = String::new();
-        for line in &self.0 {
+        for line in self.0.iter() {
             let mut new_line = String::new();
             match line {
                 Sub(sp) => {
@@ -317,7 +464,11 @@ impl<T: Display> SubProof<T> {
                 Log(l, inst) => {
                     let l = format!("{index:>3}: {}{}", "│ ".repeat(depth), l.display(true));
                     let len = l.chars().count();
-                    let space = if len < 32 { 32 - len } else { 64 - len };
+                    let space = if len < RULE_COLUMN {
+                        RULE_COLUMN - len
+                    } else {
+                        (2 * RULE_COLUMN).saturating_sub(len).max(1)
+                    };
                     writeln!(
                         &mut new_line,
                         "{l}{}{}",
@@ -355,10 +506,31 @@ impl<T: Display> SubProof<T> {
         }
         s
     }
+
+    /// The rows of a Typst table with the line number, term and rule.
+    pub fn typst(&self, index: &mut usize, depth: usize) -> String {
+        let mut s = String::new();
+        for line in self.0.iter() {
+            match line {
+                Sub(sp) => s.push_str(&sp.typst(index, depth + 1)),
+                Log(l, r) => {
+                    s.push_str(&format!(
+                        "\n  [{index}], [{}${}$], [{}],",
+                        "│ ".repeat(depth),
+                        l.typst(true),
+                        r.as_ref().map(|i| i.typst()).unwrap_or_default()
+                    ));
+                    *index += 1;
+                }
+            }
+        }
+        s
+    }
 }
 
 const SPACING: &str = "    ";
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Stats {
     pub terms: usize,
     pub lines: usize,
@@ -383,32 +555,6 @@ impl<T> FitchProof<T> {
     pub fn stats(&self) -> Stats {
         self.proof.stats()
     }
-
-    pub fn next_select(&mut self, input: &[usize]) -> Option<Vec<usize>> {
-        if input.is_empty() {
-            return None;
-        }
-
-        let mut new_input = input.to_vec();
-        for i in 0..=1 {
-            new_input.push(i);
-            if let Some(true) =
-                self.proof
-                    .recurse(&new_input, |_| false, |t| matches!(&*t, Logic::Empty))
-            {
-                return Some(new_input);
-            }
-            new_input.pop();
-        }
-        let mut new_input = input.to_vec();
-        let last = new_input.pop().unwrap();
-        if last > 1 {
-            self.next_select(&new_input)
-        } else {
-            new_input.push(last + 1);
-            self.next_select(&new_input)
-        }
-    }
 }
 impl<T: Display> FitchProof<T> {
     pub fn latex(&self) -> String {
@@ -418,14 +564,43 @@ impl<T: Display> FitchProof<T> {
         s.push_str("\n\\end{logicproof}");
         s
     }
+    /// The proof as a Typst table, including the premises.
+    pub fn typst(&self) -> String {
+        let mut s = "#table(\n  columns: 3,\n  stroke: none,".to_string();
+        for (i, p) in self.prepositions.iter().enumerate() {
+            s.push_str(&format!(
+                "\n  [{}], [${}$], [premise],",
+                i + 1,
+                p.typst(true)
+            ));
+        }
+        s.push_str(&self.proof.typst(&mut (self.prepositions.len() + 1), 0));
+        s.push_str(&format!(
+            "\n  table.hline(), [], [$tack.r {}$], [],\n)",
+            self.result.typst(true)
+        ));
+        s
+    }
+    /// The puzzle as a sequent, for example `p → q, ¬q ⊢ ¬p`.
+    pub fn sequent(&self) -> String {
+        let premises = self
+            .prepositions
+            .iter()
+            .map(|p| p.display(true))
+            .collect::<Vec<_>>();
+        format!("{} ⊢ {}", premises.join(", "), self.result.display(true))
+            .trim_start()
+            .to_string()
+    }
 }
 impl<T: Display + Clone> Display for FitchProof<T> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let sub_proof = SubProof(
             self.prepositions
                 .iter()
-                .map(|l| Line::Log(Box::new(l.clone()), Some(Premise)))
-                .collect(),
+                .map(|l| Line::Log(Ptr::new(l.clone()), Some(Premise)))
+                .collect::<Vec<_>>()
+                .into(),
         );
         write!(f, "{}", sub_proof.display(&mut 1, 0))?;
 
@@ -446,7 +621,7 @@ impl<T: Display + Clone> Display for FitchProof<T> {
 
 pub fn empty() -> FitchProof<&'static str> {
     FitchProof {
-        proof: SubProof(vec![Log(Empty.into(), None)]),
+        proof: SubProof::new(vec![Log(Empty.into(), None)]),
         prepositions: Vec::new(),
         result: Or(
             Variable("p").into(),
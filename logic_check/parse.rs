@@ -0,0 +1,245 @@
+use super::Logic;
+use std::{fmt::Display, str::FromStr};
+
+/// An error from parsing a term, `at` is the position in characters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    pub at: usize,
+    pub message: String,
+}
+impl Display for ParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at position {}", self.message, self.at + 1)
+    }
+}
+impl std::error::Error for ParseError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Token {
+    Variable(String),
+    And,
+    Or,
+    Implies,
+    Not,
+    Bottom,
+    Empty,
+    Open,
+    Close,
+    Comma,
+    Turnstile,
+}
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Variable(v) => write!(f, "\"{v}\""),
+            Token::And => write!(f, "∧"),
+            Token::Or => write!(f, "∨"),
+            Token::Implies => write!(f, "→"),
+            Token::Not => write!(f, "¬"),
+            Token::Bottom => write!(f, "⊥"),
+            Token::Empty => write!(f, "×"),
+            Token::Open => write!(f, "("),
+            Token::Close => write!(f, ")"),
+            Token::Comma => write!(f, ","),
+            Token::Turnstile => write!(f, "⊢"),
+        }
+    }
+}
+
+/// Symbols and their ASCII alternatives, longest first.
+const SYMBOLS: &[(&str, Token)] = &[
+    ("_|_", Token::Bottom),
+    ("|-", Token::Turnstile),
+    ("->", Token::Implies),
+    ("=>", Token::Implies),
+    ("/\\", Token::And),
+    ("\\/", Token::Or),
+    ("&&", Token::And),
+    ("||", Token::Or),
+    ("∧", Token::And),
+    ("&", Token::And),
+    ("∨", Token::Or),
+    ("|", Token::Or),
+    ("→", Token::Implies),
+    (">", Token::Implies),
+    ("¬", Token::Not),
+    ("!", Token::Not),
+    ("~", Token::Not),
+    ("⊥", Token::Bottom),
+    ("#", Token::Bottom),
+    ("×", Token::Empty),
+    ("?", Token::Empty),
+    ("(", Token::Open),
+    (")", Token::Close),
+    (",", Token::Comma),
+    ("⊢", Token::Turnstile),
+];
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, ParseError> {
+    let mut tokens = Vec::new();
+    let mut rest = input;
+    let mut at = 0;
+    'outer: while let Some(c) = rest.chars().next() {
+        if c.is_whitespace() {
+            rest = &rest[c.len_utf8()..];
+            at += 1;
+            continue;
+        }
+        for (s, t) in SYMBOLS {
+            if let Some(r) = rest.strip_prefix(s) {
+                tokens.push((at, t.clone()));
+                rest = r;
+                at += s.chars().count();
+                continue 'outer;
+            }
+        }
+        if c.is_alphabetic() {
+            let len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '\''))
+                .unwrap_or(rest.len());
+            tokens.push((at, Token::Variable(rest[..len].to_string())));
+            at += rest[..len].chars().count();
+            rest = &rest[len..];
+            continue;
+        }
+        return Err(ParseError {
+            at,
+            message: format!("unexpected character \"{c}\""),
+        });
+    }
+    Ok(tokens)
+}
+
+/// A recursive descent parser, binding tightest to loosest: `¬`, `∧`, `∨`, `→`.
+/// `∧` and `∨` associate to the left and `→` to the right.
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    pos: usize,
+    end: usize,
+}
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos).map(|(_, t)| t)
+    }
+
+    fn at(&self) -> usize {
+        self.tokens
+            .get(self.pos)
+            .map(|(at, _)| *at)
+            .unwrap_or(self.end)
+    }
+
+    fn error<R>(&self, expected: &str) -> Result<R, ParseError> {
+        let message = match self.peek() {
+            Some(t) => format!("expected {expected}, found {t}"),
+            None => format!("expected {expected}"),
+        };
+        Err(ParseError {
+            at: self.at(),
+            message,
+        })
+    }
+
+    fn implies(&mut self) -> Result<Logic<String>, ParseError> {
+        let left = self.or()?;
+        if self.peek() == Some(&Token::Implies) {
+            self.pos += 1;
+            let right = self.implies()?;
+            return Ok(Logic::Implies(left.into(), right.into()));
+        }
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Logic<String>, ParseError> {
+        let mut left = self.and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            left = Logic::Or(left.into(), self.and()?.into());
+        }
+        Ok(left)
+    }
+
+    fn and(&mut self) -> Result<Logic<String>, ParseError> {
+        let mut left = self.not()?;
+        while self.peek() == Some(&Token::And) {
+            self.pos += 1;
+            left = Logic::And(left.into(), self.not()?.into());
+        }
+        Ok(left)
+    }
+
+    fn not(&mut self) -> Result<Logic<String>, ParseError> {
+        if self.peek() == Some(&Token::Not) {
+            self.pos += 1;
+            return Ok(Logic::Not(self.not()?.into()));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Logic<String>, ParseError> {
+        let res = match self.peek() {
+            Some(Token::Variable(v)) => Logic::Variable(v.clone()),
+            Some(Token::Bottom) => Logic::Bottom,
+            Some(Token::Empty) => Logic::Empty,
+            Some(Token::Open) => {
+                self.pos += 1;
+                let res = self.implies()?;
+                if self.peek() != Some(&Token::Close) {
+                    return self.error("\")\"");
+                }
+                res
+            }
+            _ => return self.error("a term"),
+        };
+        self.pos += 1;
+        Ok(res)
+    }
+}
+
+impl FromStr for Logic<String> {
+    type Err = ParseError;
+
+    /// Parses a term written with either the symbols used by
+    /// [`Logic::display`] or their ASCII alternatives:
+    /// `&` for `∧`, `|` for `∨`, `->` or `>` for `→`, `!` or `~` for `¬`,
+    /// `#` for `⊥` and `?` for an empty term.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            pos: 0,
+            end: s.chars().count(),
+        };
+        let res = parser.implies()?;
+        if parser.pos < parser.tokens.len() {
+            return parser.error("the end of the term");
+        }
+        Ok(res)
+    }
+}
+
+/// Parses a sequent such as `p → q, ¬q ⊢ ¬p`: the premises separated by
+/// commas, `⊢` (or `|-`) and the conclusion. There may be no premises.
+pub fn parse_sequent(s: &str) -> Result<(Vec<Logic<String>>, Logic<String>), ParseError> {
+    let mut parser = Parser {
+        tokens: tokenize(s)?,
+        pos: 0,
+        end: s.chars().count(),
+    };
+    let mut premises = Vec::new();
+    if parser.peek() != Some(&Token::Turnstile) {
+        loop {
+            premises.push(parser.implies()?);
+            match parser.peek() {
+                Some(Token::Comma) => parser.pos += 1,
+                Some(Token::Turnstile) => break,
+                _ => return parser.error("\",\" or \"⊢\""),
+            }
+        }
+    }
+    parser.pos += 1;
+    let result = parser.implies()?;
+    if parser.pos < parser.tokens.len() {
+        return parser.error("the end of the sequent");
+    }
+    Ok((premises, result))
+}
@@ -0,0 +1,212 @@
+//! Typed paths into proofs, and navigation with them.
+
+use super::{Line, Logic, Ptr, SelectType, SubProof};
+use serde::{Deserialize, Serialize};
+
+/// A position in a proof, either a line (or sub proof) or a term in a line.
+///
+/// `line` is the same kind of path as used by [`SubProof::remove_line`]: the
+/// index of each enclosing sub proof followed by the index in the innermost
+/// one. `term` is the path to a sub term of that line, 0 for the left side
+/// and 1 for the right side. Paths are ordered as they appear in the proof.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct ProofPath {
+    pub line: Vec<usize>,
+    pub term: Vec<usize>,
+}
+impl ProofPath {
+    /// The path to a whole line or sub proof.
+    pub fn new(line: Vec<usize>) -> Self {
+        Self {
+            line,
+            term: Vec::new(),
+        }
+    }
+
+    /// True if the path is to a whole line or sub proof.
+    pub fn is_line(&self) -> bool {
+        self.term.is_empty()
+    }
+
+    /// The path to the line containing the term.
+    pub fn to_line(&self) -> Self {
+        Self::new(self.line.clone())
+    }
+
+    /// The path to a sub term, see [`Logic::get`].
+    pub fn child(&self, index: usize) -> Self {
+        let mut res = self.clone();
+        res.term.push(index);
+        res
+    }
+
+    /// The term or sub proof containing this one. Lines in the outermost
+    /// proof have no parent.
+    pub fn parent(&self) -> Option<Self> {
+        let mut res = self.clone();
+        if res.term.pop().is_none() {
+            if res.line.len() < 2 {
+                return None;
+            }
+            res.line.pop();
+        }
+        Some(res)
+    }
+}
+
+impl<T> Logic<T> {
+    fn visit_terms(&self, path: &mut ProofPath, f: &mut impl FnMut(&ProofPath, &Logic<T>)) {
+        f(path, self);
+        let children = match self {
+            Logic::Variable(_) | Logic::Bottom | Logic::Empty => 0,
+            Logic::Not(_) => 1,
+            Logic::And(_, _) | Logic::Implies(_, _) | Logic::Or(_, _) => 2,
+        };
+        for i in 0..children {
+            path.term.push(i);
+            self.get(i).unwrap().visit_terms(path, f);
+            path.term.pop();
+        }
+    }
+}
+
+impl<T> SubProof<T> {
+    fn visit_inner(&self, path: &mut Vec<usize>, f: &mut impl FnMut(&[usize], &Line<T>)) {
+        for (i, l) in self.0.iter().enumerate() {
+            path.push(i);
+            f(path, l);
+            if let Line::Sub(s) = l {
+                s.visit_inner(path, f);
+            }
+            path.pop();
+        }
+    }
+
+    /// Calls `f` with every line and sub proof, in order.
+    pub fn visit(&self, mut f: impl FnMut(&ProofPath, &Line<T>)) {
+        self.visit_inner(&mut Vec::new(), &mut |p, l| {
+            f(&ProofPath::new(p.to_vec()), l)
+        });
+    }
+
+    /// Calls `f` with every term in every line, in order, with the outermost
+    /// term of a line before its sub terms.
+    pub fn visit_terms(&self, mut f: impl FnMut(&ProofPath, &Logic<T>)) {
+        self.visit_inner(&mut Vec::new(), &mut |p, l| {
+            if let Line::Log(t, _) = l {
+                t.visit_terms(&mut ProofPath::new(p.to_vec()), &mut f);
+            }
+        });
+    }
+
+    pub fn line_at(&self, line: &[usize]) -> Option<&Line<T>> {
+        match line {
+            [i] => self.0.get(*i),
+            [i, xs @ ..] => match self.0.get(*i) {
+                Some(Line::Sub(s)) => s.line_at(xs),
+                _ => None,
+            },
+            [] => None,
+        }
+    }
+
+    pub fn term(&self, path: &ProofPath) -> Option<&Logic<T>> {
+        let Some(Line::Log(t, _)) = self.line_at(&path.line) else {
+            return None;
+        };
+        path.term.iter().try_fold(&**t, |t, i| t.get(*i))
+    }
+
+    /// What the path points to, if anything. The empty path is the whole proof.
+    pub fn select_type(&self, path: &ProofPath) -> Option<SelectType> {
+        if path.line.is_empty() {
+            return path.is_line().then_some(SelectType::SubProof);
+        }
+        match self.line_at(&path.line)? {
+            Line::Sub(_) => path.is_line().then_some(SelectType::SubProof),
+            Line::Log(_, _) => self.term(path).map(|_| SelectType::Term),
+        }
+    }
+
+    /// The line after the one containing `path`, entering and leaving sub
+    /// proofs as needed.
+    pub fn next_line(&self, path: &ProofPath) -> Option<ProofPath> {
+        let mut res = None;
+        self.visit(|p, l| {
+            if res.is_none() && matches!(l, Line::Log(_, _)) && p.line > path.line {
+                res = Some(p.clone());
+            }
+        });
+        res
+    }
+
+    /// The line before the one containing `path`, entering and leaving sub
+    /// proofs as needed.
+    pub fn prev_line(&self, path: &ProofPath) -> Option<ProofPath> {
+        let mut res = None;
+        self.visit(|p, l| {
+            if matches!(l, Line::Log(_, _)) && p.line < path.line {
+                res = Some(p.clone());
+            }
+        });
+        res
+    }
+
+    /// The term after `path`, where a term comes before its sub terms.
+    pub fn next_term(&self, path: &ProofPath) -> Option<ProofPath> {
+        let mut res = None;
+        self.visit_terms(|p, _| {
+            if res.is_none() && p > path {
+                res = Some(p.clone());
+            }
+        });
+        res
+    }
+
+    /// The term before `path`, where a term comes before its sub terms.
+    pub fn prev_term(&self, path: &ProofPath) -> Option<ProofPath> {
+        let mut res = None;
+        self.visit_terms(|p, _| {
+            if p < path {
+                res = Some(p.clone());
+            }
+        });
+        res
+    }
+
+    /// The first empty term in the proof.
+    pub fn first_empty(&self) -> Option<ProofPath> {
+        self.next_empty(&ProofPath::default())
+    }
+
+    /// The first empty term after `path`, which may be in a later line.
+    pub fn next_empty(&self, path: &ProofPath) -> Option<ProofPath> {
+        let mut res = None;
+        self.visit_terms(|p, t| {
+            if res.is_none() && matches!(t, Logic::Empty) && p > path {
+                res = Some(p.clone());
+            }
+        });
+        res
+    }
+}
+impl<T: Clone> SubProof<T> {
+    pub fn line_at_mut(&mut self, line: &[usize]) -> Option<&mut Line<T>> {
+        match line {
+            [i] => self.lines_mut().get_mut(*i),
+            [i, xs @ ..] => match self.lines_mut().get_mut(*i) {
+                Some(Line::Sub(s)) => s.line_at_mut(xs),
+                _ => None,
+            },
+            [] => None,
+        }
+    }
+
+    pub fn term_mut(&mut self, path: &ProofPath) -> Option<&mut Logic<T>> {
+        let Some(Line::Log(t, _)) = self.line_at_mut(&path.line) else {
+            return None;
+        };
+        let t = Ptr::make_mut(t);
+        path.term.iter().try_fold(t, |t, i| t.get_mut(*i))
+    }
+}
@@ -0,0 +1,205 @@
+//! Statistics, streaks and achievements over every puzzle the player has
+//! solved. They are worked out from the saved proofs, which are verified
+//! again, rather than from what the game saved about them.
+
+use super::{score::Scorecard, text::RULES, FitchProof, Instruction, Line, SubProof};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::{Debug, Display},
+    hash::Hash,
+};
+
+/// A saved proof of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solve<T> {
+    /// The puzzle, see [`super::schedule`].
+    pub day: usize,
+    /// The day the puzzle was solved on.
+    pub solved_on: usize,
+    pub proof: FitchProof<T>,
+    /// How many seconds it took, `None` if it wasn't timed.
+    pub time: Option<usize>,
+    pub undos: usize,
+    /// The par of the puzzle, if it is known.
+    pub par: Option<usize>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Achievement {
+    FirstProof,
+    TenProofs,
+    /// A proof without `LEM` or `PBC`.
+    Constructive,
+    OnPar,
+    /// A timed proof in under a minute.
+    Quick,
+    NoUndo,
+    WeekStreak,
+}
+impl Achievement {
+    pub const ALL: [Achievement; 7] = [
+        Achievement::FirstProof,
+        Achievement::TenProofs,
+        Achievement::Constructive,
+        Achievement::OnPar,
+        Achievement::Quick,
+        Achievement::NoUndo,
+        Achievement::WeekStreak,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Achievement::FirstProof => "First proof",
+            Achievement::TenProofs => "Ten proofs",
+            Achievement::Constructive => "Constructive",
+            Achievement::OnPar => "On par",
+            Achievement::Quick => "Quick",
+            Achievement::NoUndo => "No regrets",
+            Achievement::WeekStreak => "Week streak",
+        }
+    }
+
+    pub fn description(self) -> &'static str {
+        match self {
+            Achievement::FirstProof => "Solve a puzzle",
+            Achievement::TenProofs => "Solve ten puzzles",
+            Achievement::Constructive => "Solve a puzzle without LEM or PBC",
+            Achievement::OnPar => "Solve a puzzle in par or better",
+            Achievement::Quick => "Solve a timed puzzle in under a minute",
+            Achievement::NoUndo => "Solve a puzzle without undoing anything",
+            Achievement::WeekStreak => "Solve the puzzle of the day seven days in a row",
+        }
+    }
+}
+
+/// The upper ends of the ranges of times, in seconds, that
+/// [`Summary::distribution`] counts. The last range has no end.
+pub const TIME_BUCKETS: [usize; 4] = [30, 60, 120, 300];
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Summary {
+    /// The number of puzzles with a valid proof.
+    pub solved: usize,
+    /// The puzzles of the day solved on their day in a row, up to today, or
+    /// yesterday if today's puzzle hasn't been solved yet.
+    pub current_streak: usize,
+    pub longest_streak: usize,
+    /// The times of the timed proofs, fastest first.
+    pub times: Vec<usize>,
+    /// How many lines used each rule, in the order of [`RULES`], without
+    /// the rules that were never used.
+    pub rules: Vec<(&'static str, usize)>,
+    pub achievements: Vec<Achievement>,
+}
+
+impl Summary {
+    /// Works out the statistics from the proofs, ignoring the ones that
+    /// don't prove their puzzle. Only one proof of every puzzle is counted.
+    pub fn new<T: Clone + Hash + Eq + Debug + Display>(
+        solves: impl IntoIterator<Item = Solve<T>>,
+        today: usize,
+    ) -> Self {
+        let mut days = BTreeSet::new();
+        let mut on_the_day = BTreeSet::new();
+        let mut times = Vec::new();
+        let mut rules = HashMap::new();
+        let mut earned = Vec::new();
+        for mut solve in solves {
+            if solve.proof.verify() != Ok(true) || !days.insert(solve.day) {
+                continue;
+            }
+            if solve.solved_on == solve.day {
+                on_the_day.insert(solve.day);
+            }
+            times.extend(solve.time);
+
+            let mut used = Vec::new();
+            instructions(&solve.proof.proof, &mut used);
+            for rule in &used {
+                *rules.entry(*rule).or_insert(0) += 1;
+            }
+            if !used.iter().any(|r| *r == "LEM" || *r == "PBC") {
+                earned.push(Achievement::Constructive);
+            }
+            let scorecard = Scorecard::new(solve.proof.stats(), solve.undos, solve.par, None);
+            if scorecard.over_par().is_some_and(|o| o <= 0) {
+                earned.push(Achievement::OnPar);
+            }
+            if solve.time.is_some_and(|t| t < 60) {
+                earned.push(Achievement::Quick);
+            }
+            if solve.undos == 0 {
+                earned.push(Achievement::NoUndo);
+            }
+        }
+        times.sort();
+
+        let mut longest_streak = 0;
+        let mut streak = 0;
+        let mut last = None;
+        for day in &on_the_day {
+            streak = if last.is_some_and(|l| l + 1 == *day) {
+                streak + 1
+            } else {
+                1
+            };
+            longest_streak = longest_streak.max(streak);
+            last = Some(*day);
+        }
+        let end = if on_the_day.contains(&today) {
+            Some(today)
+        } else {
+            today.checked_sub(1)
+        };
+        let current_streak = end.map_or(0, |end| {
+            (0..=end)
+                .rev()
+                .take_while(|d| on_the_day.contains(d))
+                .count()
+        });
+
+        if !days.is_empty() {
+            earned.push(Achievement::FirstProof);
+        }
+        if days.len() >= 10 {
+            earned.push(Achievement::TenProofs);
+        }
+        if longest_streak >= 7 {
+            earned.push(Achievement::WeekStreak);
+        }
+        Summary {
+            solved: days.len(),
+            current_streak,
+            longest_streak,
+            times,
+            rules: RULES
+                .iter()
+                .filter_map(|(r, _)| Some((*r, *rules.get(r)?)))
+                .collect(),
+            achievements: Achievement::ALL
+                .into_iter()
+                .filter(|a| earned.contains(a))
+                .collect(),
+        }
+    }
+
+    /// How many times fall in each range of [`TIME_BUCKETS`], and after
+    /// the last one.
+    pub fn distribution(&self) -> [usize; TIME_BUCKETS.len() + 1] {
+        let mut res = [0; TIME_BUCKETS.len() + 1];
+        for t in &self.times {
+            res[TIME_BUCKETS.partition_point(|b| b <= t)] += 1;
+        }
+        res
+    }
+}
+
+/// The rule of every line, in order.
+fn instructions<T>(proof: &SubProof<T>, out: &mut Vec<&'static str>) {
+    for line in proof.0.iter() {
+        match line {
+            Line::Sub(s) => instructions(s, out),
+            Line::Log(_, inst) => out.push(inst.as_ref().unwrap_or(&Instruction::Invalid).rule()),
+        }
+    }
+}
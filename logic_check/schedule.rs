@@ -0,0 +1,73 @@
+//! Which puzzle is played on which day.
+//!
+//! Days are counted in UTC from [`START`], and each begins [`ROLLOVER`]
+//! seconds after midnight, so every player gets the same puzzle. Puzzles are
+//! identified by their index in the puzzle list, which is only ever appended
+//! to. The schedule is a list of eras, each cycling through some puzzles from
+//! its first day until the next era begins. New eras are only ever appended,
+//! starting on a future day, so that puzzle #N always stays the same puzzle.
+
+use serde::{Deserialize, Serialize};
+
+/// The beginning of day 0, 2024-08-07 at midnight UTC, in seconds since the
+/// Unix epoch.
+pub const START: i64 = 1722988800;
+
+/// How many seconds after midnight UTC a new day begins, 08:52:37. This is
+/// when the first puzzle was published, and days have been counted from it
+/// since, so it can't change without renumbering the puzzles played so far.
+pub const ROLLOVER: i64 = 8 * 60 * 60 + 52 * 60 + 37;
+
+const DAY: i64 = 24 * 60 * 60;
+
+/// The day at a time in seconds since the Unix epoch. Times before day 0
+/// belong to it.
+pub fn day(time: i64) -> usize {
+    (time - START - ROLLOVER).div_euclid(DAY).max(0) as usize
+}
+
+/// When a day begins, in seconds since the Unix epoch.
+pub fn day_start(day: usize) -> i64 {
+    START + ROLLOVER + day as i64 * DAY
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Era {
+    /// The first day of the era.
+    pub from: usize,
+    /// The puzzles played in turn, by their index in the puzzle list.
+    pub puzzles: Vec<usize>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Schedule(pub Vec<Era>);
+impl Schedule {
+    /// The puzzle of the day.
+    pub fn puzzle(&self, day: usize) -> Option<usize> {
+        let era = self.0.iter().rev().find(|e| e.from <= day)?;
+        era.puzzles
+            .get((day - era.from) % era.puzzles.len().max(1))
+            .copied()
+    }
+
+    /// Checks that the eras are in order, starting on day 0, and only use
+    /// puzzles from a list of `puzzles`.
+    pub fn check(&self, puzzles: usize) -> Result<(), String> {
+        if self.0.first().map(|e| e.from) != Some(0) {
+            return Err("the first era must start on day 0".to_string());
+        }
+        for (i, era) in self.0.iter().enumerate() {
+            if i > 0 && era.from <= self.0[i - 1].from {
+                return Err(format!("era {i} doesn't start after the one before it"));
+            }
+            if era.puzzles.is_empty() {
+                return Err(format!("era {i} has no puzzles"));
+            }
+            if let Some(p) = era.puzzles.iter().find(|p| **p >= puzzles) {
+                return Err(format!("era {i} plays puzzle {p}, but there are {puzzles}"));
+            }
+        }
+        Ok(())
+    }
+}
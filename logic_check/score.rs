@@ -0,0 +1,68 @@
+//! Scores a win like golf. Every line of the proof is a stroke, and so are
+//! every few undos. The par of a puzzle is the fewest lines any proof of it is
+//! known to take, so a proof at par is as short as the best known one.
+
+use super::Stats;
+use std::fmt::Display;
+
+/// How many undos cost as much as a line.
+pub const UNDOS_PER_STROKE: usize = 5;
+
+/// The points for a proof at par, before the time is taken off.
+pub const PAR_POINTS: usize = 1000;
+
+/// The points a stroke over or under par costs or earns.
+pub const STROKE_POINTS: usize = 100;
+
+/// The most points the time can take off, at one point per
+/// [`SECONDS_PER_POINT`] seconds.
+pub const MAX_TIME_POINTS: usize = 500;
+
+pub const SECONDS_PER_POINT: usize = 6;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Scorecard {
+    /// The lines of the proof and the undos, see [`UNDOS_PER_STROKE`].
+    pub strokes: usize,
+    /// The fewest lines a proof of the puzzle is known to take.
+    pub par: Option<usize>,
+    pub points: usize,
+}
+
+impl Scorecard {
+    /// Scores a proof with the stats, made with `undos` undos in `time`
+    /// seconds, or without a timer.
+    pub fn new(stats: Stats, undos: usize, par: Option<usize>, time: Option<usize>) -> Self {
+        let strokes = stats.lines + undos / UNDOS_PER_STROKE;
+        let par_points = match par {
+            Some(par) if strokes > par => {
+                PAR_POINTS.saturating_sub((strokes - par) * STROKE_POINTS)
+            }
+            Some(par) => PAR_POINTS + (par - strokes) * STROKE_POINTS,
+            None => PAR_POINTS,
+        };
+        let time_points = time.map_or(0, |t| (t / SECONDS_PER_POINT).min(MAX_TIME_POINTS));
+        Self {
+            strokes,
+            par,
+            points: par_points.saturating_sub(time_points),
+        }
+    }
+
+    /// How many strokes over par the proof took, negative if under.
+    pub fn over_par(&self) -> Option<isize> {
+        self.par.map(|par| self.strokes as isize - par as isize)
+    }
+}
+
+/// Like `par +2, 800 points`, or just the points without a par.
+impl Display for Scorecard {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.over_par() {
+            Some(0) => write!(f, "par, ")?,
+            Some(over) => write!(f, "par {over:+}, ")?,
+            None => {}
+        }
+        write!(f, "{} points", self.points)
+    }
+}
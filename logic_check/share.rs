@@ -0,0 +1,188 @@
+//! A compact text encoding of puzzles and proofs, small enough to be put in
+//! the fragment of a link.
+//!
+//! Terms are written in prefix notation, with `*` for ∧, `+` for ∨, `-` for
+//! →, `!` for ¬, `@` for ⊥ and `?` for an empty term. Variables are written
+//! by name, separated by `.` when two follow each other. The premises are
+//! separated by `,` and followed by `;` and the result. A proof follows after
+//! another `;`, with its lines separated by `,` and sub proofs in parentheses.
+//! So `p-q,p;q;q` is the proof of `p → q, p ⊢ q` with the single line `q`.
+//! Justifications are not stored, they are recomputed when the proof is
+//! verified.
+
+use super::{FitchProof, Line, Logic, SubProof};
+use std::fmt::Display;
+
+/// Encodes the puzzle, and the proof if `with_proof` is set.
+pub fn encode<T: Display>(proof: &FitchProof<T>, with_proof: bool) -> String {
+    let mut out = String::new();
+    for (i, p) in proof.prepositions.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        term(&mut out, p);
+    }
+    out.push(';');
+    term(&mut out, &proof.result);
+    if with_proof {
+        out.push(';');
+        lines(&mut out, &proof.proof);
+    }
+    out
+}
+
+fn is_name(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '\''
+}
+
+fn term<T: Display>(out: &mut String, logic: &Logic<T>) {
+    match logic {
+        Logic::Variable(v) => {
+            if out.ends_with(is_name) {
+                out.push('.');
+            }
+            out.push_str(&v.to_string());
+        }
+        Logic::And(a, b) | Logic::Or(a, b) | Logic::Implies(a, b) => {
+            out.push(match logic {
+                Logic::And(..) => '*',
+                Logic::Or(..) => '+',
+                _ => '-',
+            });
+            term(out, a);
+            term(out, b);
+        }
+        Logic::Not(a) => {
+            out.push('!');
+            term(out, a);
+        }
+        Logic::Bottom => out.push('@'),
+        Logic::Empty => out.push('?'),
+    }
+}
+
+fn lines<T: Display>(out: &mut String, proof: &SubProof<T>) {
+    for (i, line) in proof.0.iter().enumerate() {
+        if i > 0 {
+            out.push(',');
+        }
+        match line {
+            Line::Log(l, _) => term(out, l),
+            Line::Sub(s) => {
+                out.push('(');
+                lines(out, s);
+                out.push(')');
+            }
+        }
+    }
+}
+
+/// Decodes a puzzle or proof. Puzzles without a proof get an empty one.
+pub fn decode(s: &str) -> Result<FitchProof<String>, String> {
+    let mut d = Decoder { s, at: 0 };
+    let mut prepositions = Vec::new();
+    if d.peek() != Some(';') {
+        loop {
+            prepositions.push(d.term()?);
+            if !d.eat(',') {
+                break;
+            }
+        }
+    }
+    d.expect(';')?;
+    let result = d.term()?;
+    let proof = if d.eat(';') {
+        d.lines()?
+    } else {
+        SubProof::default()
+    };
+    if d.peek().is_some() {
+        return d.error("the end of the link");
+    }
+    Ok(FitchProof {
+        proof,
+        prepositions,
+        result: result.into(),
+    })
+}
+
+struct Decoder<'a> {
+    s: &'a str,
+    at: usize,
+}
+
+impl Decoder<'_> {
+    fn peek(&self) -> Option<char> {
+        self.s[self.at..].chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        let found = self.peek() == Some(c);
+        if found {
+            self.at += c.len_utf8();
+        }
+        found
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c) {
+            Ok(())
+        } else {
+            self.error(&format!("{c:?}"))
+        }
+    }
+
+    fn error<R>(&self, expected: &str) -> Result<R, String> {
+        match self.peek() {
+            Some(c) => Err(format!(
+                "expected {expected} but found {c:?} at {}",
+                self.at
+            )),
+            None => Err(format!("expected {expected} but the link ended")),
+        }
+    }
+
+    fn term(&mut self) -> Result<Logic<String>, String> {
+        self.eat('.');
+        let Some(c) = self.peek() else {
+            return self.error("a term");
+        };
+        if c.is_alphabetic() {
+            let rest = &self.s[self.at..];
+            let len = rest.find(|c| !is_name(c)).unwrap_or(rest.len());
+            self.at += len;
+            return Ok(Logic::Variable(rest[..len].to_string()));
+        }
+        if !"*+-!@?".contains(c) {
+            return self.error("a term");
+        }
+        self.at += 1;
+        Ok(match c {
+            '*' => Logic::And(self.term()?.into(), self.term()?.into()),
+            '+' => Logic::Or(self.term()?.into(), self.term()?.into()),
+            '-' => Logic::Implies(self.term()?.into(), self.term()?.into()),
+            '!' => Logic::Not(self.term()?.into()),
+            '@' => Logic::Bottom,
+            _ => Logic::Empty,
+        })
+    }
+
+    fn lines(&mut self) -> Result<SubProof<String>, String> {
+        let mut lines = Vec::new();
+        if matches!(self.peek(), None | Some(')')) {
+            return Ok(SubProof::new(lines));
+        }
+        loop {
+            if self.eat('(') {
+                lines.push(Line::Sub(self.lines()?));
+                self.expect(')')?;
+            } else {
+                lines.push(Line::Log(self.term()?.into(), None));
+            }
+            if !self.eat(',') {
+                break;
+            }
+        }
+        Ok(SubProof::new(lines))
+    }
+}
@@ -0,0 +1,24 @@
+//! Fixtures shared by the integration tests. Not every test uses all of them.
+#![allow(dead_code)]
+
+use logic_check::{FitchProof, Line, Logic, SubProof};
+
+pub fn term(s: &str) -> Logic<String> {
+    s.parse().unwrap()
+}
+
+pub fn line(s: &str) -> Line<String> {
+    Line::Log(term(s).into(), None)
+}
+
+/// `p → q, ¬q ⊢ ¬p`, proven by contradiction.
+pub fn proof() -> FitchProof<String> {
+    FitchProof {
+        proof: SubProof::new(vec![
+            Line::Sub(SubProof::new(vec![line("p"), line("q"), line("⊥")])),
+            line("¬p"),
+        ]),
+        prepositions: vec![term("p → q"), term("¬q")],
+        result: term("¬p").into(),
+    }
+}
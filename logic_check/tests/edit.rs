@@ -0,0 +1,153 @@
+use logic_check::{Edited, Line, LineEdit, Logic, ProofPath, Ptr, SubProof};
+
+fn line(v: &'static str) -> Line<&'static str> {
+    Line::Log(Logic::Variable(v).into(), None)
+}
+
+/// `a [b c] d`
+fn proof() -> SubProof<&'static str> {
+    SubProof::new(vec![
+        line("a"),
+        Line::Sub(SubProof::new(vec![line("b"), line("c")])),
+        line("d"),
+    ])
+}
+
+/// The proof written with brackets around sub proofs.
+fn show(proof: &SubProof<&'static str>) -> String {
+    proof
+        .0
+        .iter()
+        .map(|l| match l {
+            Line::Sub(s) => format!("[{}]", show(s)),
+            Line::Log(l, _) => l.display(true),
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[test]
+fn insert() {
+    let mut p = proof();
+    assert_eq!(p.insert_before(&[1, 0], line("x")), Some(vec![1, 0]));
+    assert_eq!(p.insert_after(&[1, 2], line("y")), Some(vec![1, 3]));
+    assert_eq!(p.insert_line(&[3], line("z")), Some(vec![3]));
+    assert_eq!(show(&p), "a [x b c y] d z");
+
+    assert_eq!(p.insert_before(&[4], line("x")), None);
+    assert_eq!(p.insert_line(&[0, 0], line("x")), None);
+    assert_eq!(p.insert_after(&[], line("x")), None);
+    assert_eq!(show(&p), "a [x b c y] d z");
+}
+
+#[test]
+fn move_lines() {
+    let mut p = proof();
+    assert_eq!(p.move_down(&[0]), Some(vec![1]));
+    assert_eq!(show(&p), "[b c] a d");
+    assert_eq!(p.move_up(&[0, 1]), Some(vec![0, 0]));
+    assert_eq!(p.move_up(&[0, 0]), Some(vec![0]));
+    assert_eq!(show(&p), "c [b] a d");
+    assert_eq!(p.move_down(&[1, 0]), Some(vec![2]));
+    assert_eq!(show(&p), "c [] b a d");
+
+    assert_eq!(p.move_up(&[0]), None);
+    assert_eq!(p.move_down(&[4]), None);
+    assert_eq!(p.move_down(&[5]), None);
+}
+
+#[test]
+fn wrap_and_unwrap() {
+    let mut p = proof();
+    assert_eq!(p.wrap(&[1], 2), Some(vec![1]));
+    assert_eq!(show(&p), "a [[b c] d]");
+    assert_eq!(p.wrap(&[1, 1], 2), None);
+    assert_eq!(p.wrap(&[0], 0), None);
+    assert_eq!(p.unwrap(&[1, 0]), Some(vec![1, 0]));
+    assert_eq!(show(&p), "a [b c d]");
+    assert_eq!(p.unwrap(&[0]), None);
+    assert_eq!(p.unwrap(&[1]), Some(vec![1]));
+    assert_eq!(show(&p), "a b c d");
+}
+
+#[test]
+fn duplicate() {
+    let mut p = proof();
+    assert_eq!(p.duplicate(&[1, 0]), Some(vec![1, 1]));
+    assert_eq!(p.duplicate(&[1]), Some(vec![2]));
+    assert_eq!(show(&p), "a [b b c] [b b c] d");
+    assert_eq!(p.duplicate(&[5]), None);
+}
+
+#[test]
+fn split_and_merge() {
+    let mut p = proof();
+    assert_eq!(p.split(&[1, 0]), None);
+    assert_eq!(p.split(&[1, 2]), None);
+    assert_eq!(p.split(&[0]), None);
+    assert_eq!(p.split(&[1, 1]), Some(vec![2]));
+    assert_eq!(show(&p), "a [b] [c] d");
+    assert_eq!(p.merge(&[0]), None);
+    assert_eq!(p.merge(&[2]), None);
+    assert_eq!(p.merge(&[1]), Some(vec![1, 1]));
+    assert_eq!(show(&p), "a [b c] d");
+}
+
+#[test]
+fn line_edits() {
+    let edited = |selected: Vec<usize>, changed: Vec<usize>| Some(Edited { selected, changed });
+    let mut p = proof();
+    assert_eq!(
+        LineEdit::Wrap.apply(&mut p, &[2]),
+        edited(vec![2, 0], vec![2])
+    );
+    assert_eq!(show(&p), "a [b c] [d]");
+    assert_eq!(
+        LineEdit::Merge.apply(&mut p, &[1, 1]),
+        edited(vec![1, 1], vec![1])
+    );
+    assert_eq!(show(&p), "a [b c d]");
+    assert_eq!(
+        LineEdit::Unwrap.apply(&mut p, &[1, 2]),
+        edited(vec![3], vec![1])
+    );
+    assert_eq!(show(&p), "a b c d");
+    assert_eq!(
+        LineEdit::MoveUp.apply(&mut p, &[1]),
+        edited(vec![0], vec![0])
+    );
+    assert_eq!(show(&p), "b a c d");
+    assert_eq!(
+        LineEdit::InsertBelow.apply(&mut p, &[3]),
+        edited(vec![4], vec![3])
+    );
+    assert_eq!(show(&p), "b a c d ×");
+
+    assert_eq!(LineEdit::Unwrap.apply(&mut p, &[1]), None);
+    assert_eq!(LineEdit::Split.apply(&mut p, &[1]), None);
+    assert_eq!(LineEdit::Duplicate.apply(&mut p, &[]), None);
+    assert_eq!(show(&p), "b a c d ×");
+}
+
+#[test]
+fn clones_share_unchanged_parts() {
+    let mut p = proof();
+    let snapshot = p.clone();
+    assert!(Ptr::ptr_eq(&p.0, &snapshot.0));
+
+    p.insert_after(&[0], line("x"));
+    assert_eq!(show(&p), "a x [b c] d");
+    assert_eq!(show(&snapshot), "a [b c] d");
+    assert!(!Ptr::ptr_eq(&p.0, &snapshot.0));
+    let (Line::Sub(a), Line::Sub(b)) = (&p.0[2], &snapshot.0[1]) else {
+        unreachable!()
+    };
+    assert!(Ptr::ptr_eq(&a.0, &b.0));
+
+    let Some(Logic::Variable(v)) = p.term_mut(&ProofPath::new(vec![2, 0])) else {
+        unreachable!()
+    };
+    *v = "y";
+    assert_eq!(show(&p), "a x [y c] d");
+    assert_eq!(show(&snapshot), "a [b c] d");
+}
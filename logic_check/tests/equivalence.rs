@@ -0,0 +1,76 @@
+use std::time::{Duration, Instant};
+
+use logic_check::{entails, find_duplicates, FitchProof, Logic, SubProof, MAX_VARIABLES};
+
+mod common;
+use common::term;
+
+fn puzzle(prepositions: &[&str], result: &str) -> FitchProof<String> {
+    FitchProof {
+        proof: SubProof::default(),
+        prepositions: prepositions.iter().map(|p| term(p)).collect(),
+        result: term(result).into(),
+    }
+}
+
+/// `a → z, b → z, …` with `n` premises that only differ in their names.
+fn ties(n: usize) -> Vec<String> {
+    (0..n).map(|i| format!("v{i} → z")).collect()
+}
+
+#[test]
+fn canonical_ignores_chain_order() {
+    let a = puzzle(&["p → q", "q → r", "r → s"], "p → s");
+    let b = puzzle(&["c → d", "a → b", "b → c"], "a → d");
+    assert_eq!(a.canonical(), b.canonical());
+    let c = puzzle(&["p → q", "r → q", "r → s"], "p → s");
+    assert_ne!(a.canonical(), c.canonical());
+}
+
+#[test]
+fn canonical_ignores_order_of_ties() {
+    let a = puzzle(&["p ∧ q", "q ∧ r", "r ∧ s", "s ∧ t", "t ∧ p"], "p");
+    let b = puzzle(&["c ∧ d", "a ∧ e", "e ∧ b", "b ∧ c", "d ∧ a"], "e");
+    assert_eq!(a.canonical(), b.canonical());
+}
+
+#[test]
+fn canonical_is_fast_with_many_ties() {
+    let premises = ties(40);
+    let premises = premises.iter().map(String::as_str).collect::<Vec<_>>();
+    let mut reversed = premises.clone();
+    reversed.reverse();
+
+    let start = Instant::now();
+    let a = puzzle(&premises, "z").canonical();
+    let b = puzzle(&reversed, "z").canonical();
+    assert!(start.elapsed() < Duration::from_secs(1));
+    assert_eq!(a, b);
+}
+
+#[test]
+fn find_duplicates_is_fast_with_many_ties() {
+    let premises = ties(12);
+    let premises = premises.iter().map(String::as_str).collect::<Vec<_>>();
+    let puzzles = vec![puzzle(&premises, "z"); 10];
+
+    let start = Instant::now();
+    assert_eq!(find_duplicates(&puzzles).len(), 45);
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn too_many_variables() {
+    let all = |n: usize| {
+        (1..n).fold(Logic::Variable(0), |l, v| {
+            Logic::And(l.into(), Logic::Variable(v).into())
+        })
+    };
+    let few = all(3);
+    assert!(few.equivalent(&few));
+    assert!(entails(&[all(3)], &few));
+
+    let too_many = all(MAX_VARIABLES + 1);
+    assert!(!too_many.equivalent(&too_many));
+    assert!(!entails(&[all(MAX_VARIABLES + 1)], &too_many));
+}
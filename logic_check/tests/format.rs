@@ -0,0 +1,85 @@
+mod common;
+
+use common::{proof, term};
+use logic_check::{
+    format::{Document, Metadata, ProofFile, Puzzle, VERSION},
+    FitchProof, SubProof,
+};
+
+#[test]
+fn migrates_game_puzzles() {
+    let json = include_str!("../../data.json");
+    let old = serde_json::from_str::<Vec<FitchProof<String>>>(json).unwrap();
+    let file = ProofFile::from_json(json).unwrap();
+    assert_eq!(file.version, VERSION);
+    assert!(matches!(file.document, Document::Pack { .. }));
+    let puzzles = file.puzzles().iter().map(Puzzle::to_proof);
+    assert_eq!(puzzles.collect::<Vec<_>>(), old);
+}
+
+#[test]
+fn migrates_proofs() {
+    let json = serde_json::to_string(&proof()).unwrap();
+    let file = ProofFile::from_json(&json).unwrap();
+    assert!(matches!(file.document, Document::Proof(_)));
+    assert_eq!(file.puzzles()[0].to_proof(), proof());
+}
+
+#[test]
+fn proof_round_trip() {
+    let file = ProofFile::new(Document::Proof(Puzzle::new(&proof(), true)));
+    assert_eq!(ProofFile::from_json(&file.to_json()), Ok(file.clone()));
+    assert_eq!(file.puzzles()[0].to_proof(), proof());
+
+    let puzzle = ProofFile::new(Document::Proof(Puzzle::new(&proof(), false)));
+    assert_eq!(ProofFile::from_json(&puzzle.to_json()), Ok(puzzle.clone()));
+    assert_eq!(puzzle.puzzles()[0].to_proof().proof, SubProof::default());
+}
+
+#[test]
+fn pack_round_trip() {
+    let mut second = Puzzle::new(&proof(), false);
+    second.result = (&term("p ∨ ¬p")).into();
+    second.metadata.title = Some("Excluded middle".to_string());
+    let file = ProofFile::new(Document::Pack {
+        metadata: Metadata {
+            author: Some("someone".to_string()),
+            ..Metadata::default()
+        },
+        puzzles: vec![Puzzle::new(&proof(), true), second],
+    });
+    assert_eq!(ProofFile::from_json(&file.to_json()), Ok(file));
+}
+
+#[test]
+fn rejects_newer_versions() {
+    let file = ProofFile::new(Document::Proof(Puzzle::new(&proof(), false)));
+    let newer = file.to_json().replacen(
+        &format!("\"version\": {VERSION}"),
+        &format!("\"version\": {}", VERSION + 1),
+        1,
+    );
+    assert!(ProofFile::from_json(&newer).is_err());
+    let huge = file.to_json().replacen(
+        &format!("\"version\": {VERSION}"),
+        &format!("\"version\": {}", u32::MAX as u64 + 1),
+        1,
+    );
+    assert!(ProofFile::from_json(&huge).is_err());
+}
+
+#[test]
+fn rejects_malformed_v0_terms() {
+    let invalid = [
+        r#"{"prepositions": [], "result": {"Xor": [{"Variable": "p"}, {"Variable": "q"}]}}"#,
+        r#"{"prepositions": [], "result": {"And": [{"Variable": "p"}]}}"#,
+        r#"{"prepositions": [], "result": "Top"}"#,
+        r#"{"prepositions": [1], "result": "Bottom"}"#,
+        r#"{"prepositions": [], "result": {"Not": "Bottom", "And": []}}"#,
+        r#"[{"result": "Bottom"}]"#,
+        r#"{"prepositions": [], "result": "Bottom", "proof": [{"Log": []}]}"#,
+    ];
+    for json in invalid {
+        assert!(ProofFile::from_json(json).is_err(), "{json}");
+    }
+}
@@ -0,0 +1,36 @@
+use logic_check::{grid::grid, text};
+
+#[test]
+fn shape() {
+    let mut proof = text::parse(
+        "p → q, q → r ⊢ p → r
+         │ p
+         │ q
+         │ r
+         p → r",
+    )
+    .unwrap();
+    assert_eq!(proof.verify(), Ok(true));
+    assert_eq!(grid(&proof.proof), "⬜🟪\n⬜🟦\n⬜🟦\n🟩");
+}
+
+#[test]
+fn classical_and_invalid() {
+    let mut proof = text::parse(
+        "⊢ ¬p ∨ p
+         ¬p ∨ p
+         q",
+    )
+    .unwrap();
+    let _ = proof.verify();
+    assert_eq!(grid(&proof.proof), "🟥\n⬛");
+}
+
+/// Nothing about the terms shows in the grid.
+#[test]
+fn spoiler_free() {
+    let mut a = text::parse("p ⊢ p ∨ q\np ∨ q").unwrap();
+    let mut b = text::parse("r ⊢ r ∨ (s ∧ s)\nr ∨ (s ∧ s)").unwrap();
+    let _ = (a.verify(), b.verify());
+    assert_eq!(grid(&a.proof), grid(&b.proof));
+}
@@ -0,0 +1,187 @@
+use logic_check::{
+    generate::{self, Rng},
+    FitchProof, History, Line, Logic, ProofPath, Restored, SubProof, Verifier,
+};
+use proptest::prelude::*;
+
+/// A random structural edit or change of a term, like the ones made in the
+/// editor. Returns false if nothing could be done.
+fn edit(proof: &mut SubProof<&'static str>, rng: &mut Rng) -> bool {
+    let mut lines = Vec::new();
+    proof.visit(|p, _| lines.push(p.line.clone()));
+    if lines.is_empty() {
+        return proof
+            .insert_line(&[0], Line::Log(Logic::Empty.into(), None))
+            .is_some();
+    }
+    let at = rng.pick(&lines).clone();
+    let empty = || Line::Log(Logic::Empty.into(), None);
+    let res = match rng.below(9) {
+        0 => proof.insert_before(&at, empty()),
+        1 => proof.insert_after(&at, empty()),
+        2 => proof.move_up(&at),
+        3 => proof.move_down(&at),
+        4 => proof.duplicate(&at),
+        5 => proof.wrap(&at, 1),
+        6 => proof.unwrap(&at),
+        7 => {
+            proof.remove_line(&at);
+            Some(at)
+        }
+        _ => proof
+            .term_mut(&ProofPath::new(at.clone()))
+            .map(|t| *t = generate::logic(rng, &["p", "q"], 2))
+            .map(|_| at),
+    };
+    res.is_some()
+}
+
+proptest! {
+    #[test]
+    fn undo_and_redo_restore_snapshots(seed in any::<u64>(), edits in 1..30usize) {
+        let mut rng = Rng::new(seed);
+        let mut proof = generate::proof(&mut rng, &["p", "q", "r"], 2, 15).proof;
+        let mut snapshots = vec![proof.clone()];
+        let mut history = History::new();
+        for _ in 0..edits {
+            let before = proof.clone();
+            if edit(&mut proof, &mut rng) && history.record(&before, &proof, None) {
+                snapshots.push(proof.clone());
+            }
+        }
+        prop_assert_eq!(history.len(), snapshots.len() - 1);
+
+        while history.undo(&mut proof).is_some() {
+            prop_assert_eq!(&proof, &snapshots[history.position()]);
+        }
+        prop_assert_eq!(&proof, &snapshots[0]);
+        while history.redo(&mut proof).is_some() {
+            prop_assert_eq!(&proof, &snapshots[history.position()]);
+        }
+
+        for _ in 0..5 {
+            let target = rng.below(snapshots.len());
+            history.jump(&mut proof, target);
+            prop_assert_eq!(&proof, &snapshots[target]);
+        }
+    }
+
+    #[test]
+    fn reverify_after_undo(seed in any::<u64>(), edits in 1..20usize, jumps in prop::collection::vec(any::<usize>(), 1..10)) {
+        let mut rng = Rng::new(seed);
+        let mut proof: FitchProof<&'static str> = generate::proof(&mut rng, &["p", "q", "r"], 2, 15);
+        let mut history = History::new();
+        for _ in 0..edits {
+            let before = proof.proof.clone();
+            if edit(&mut proof.proof, &mut rng) {
+                history.record(&before, &proof.proof, None);
+            }
+        }
+        let mut verifier = Verifier::new();
+        let _ = verifier.verify(&mut proof);
+        for target in jumps {
+            let target = target % (history.len() + 1);
+            let Some(Restored { edit, .. }) = history.jump(&mut proof.proof, target) else {
+                continue;
+            };
+            let incremental = verifier.reverify(&mut proof, &edit);
+            let mut full = proof.clone();
+            prop_assert_eq!(incremental, full.verify());
+            prop_assert_eq!(&proof, &full);
+        }
+    }
+}
+
+#[test]
+fn record_discards_redo() {
+    let line = |v| Line::Log(Logic::Variable(v).into(), None);
+    let a = SubProof::new(vec![line("p")]);
+    let b = SubProof::new(vec![line("p"), line("q")]);
+    let c = SubProof::new(vec![line("p"), line("r")]);
+
+    let mut history = History::new();
+    let mut proof = b.clone();
+    assert!(history.record(&a, &b, Some(ProofPath::new(vec![0]))));
+    assert!(!history.record(&b, &b, None));
+
+    let restored = history.undo(&mut proof).unwrap();
+    assert_eq!(proof, a);
+    assert_eq!(restored.edit, [1]);
+    assert_eq!(restored.selection, Some(ProofPath::new(vec![0])));
+    assert!(history.can_redo());
+
+    assert!(history.record(&a, &c, None));
+    assert!(!history.can_redo());
+    assert_eq!(history.len(), 1);
+}
+
+#[test]
+fn json_round_trip() {
+    let line = |v| Line::Log(Logic::Variable(v).into(), None);
+    let a = SubProof::new(vec![line("p")]);
+    let b = SubProof::new(vec![line("p"), Line::Sub(SubProof::new(vec![line("q")]))]);
+
+    let mut history = History::new();
+    let mut proof = b.clone();
+    history.record(&a, &b, Some(ProofPath::new(vec![0])));
+    history.undo(&mut proof);
+
+    let json = serde_json::to_string(&history).unwrap();
+    let mut loaded = serde_json::from_str::<History<&str>>(&json).unwrap();
+    assert_eq!(loaded, history);
+    loaded.redo(&mut proof);
+    assert_eq!(proof, b);
+}
+
+#[test]
+fn rename_keeps_edits() {
+    let line = |v| Line::Log(Logic::Variable(v).into(), None);
+    let a = SubProof::new(vec![line("p")]);
+    let b = SubProof::new(vec![line("p"), Line::Sub(SubProof::new(vec![line("q")]))]);
+
+    let mut history = History::new();
+    history.record(&a, &b, None);
+    history.undo(&mut b.clone());
+
+    let mut renamed = history.rename(&mut |v| v.to_uppercase());
+    assert_eq!(renamed.position(), 0);
+    assert_eq!(renamed.undos(), 1);
+    let mut proof = a.rename(&mut |v| v.to_uppercase());
+    renamed.redo(&mut proof);
+    assert_eq!(proof, b.rename(&mut |v| v.to_uppercase()));
+}
+
+#[test]
+fn recent_keeps_the_last_edits() {
+    let line = |v| Line::Log(Logic::Variable(v).into(), None);
+    let snapshots = ["p", "q", "r", "s", "t"]
+        .iter()
+        .scan(Vec::new(), |lines, v| {
+            lines.push(line(*v));
+            Some(SubProof::new(lines.clone()))
+        })
+        .collect::<Vec<_>>();
+    let mut history = History::new();
+    for w in snapshots.windows(2) {
+        history.record(&w[0], &w[1], None);
+    }
+    let mut proof = snapshots[4].clone();
+    history.undo(&mut proof);
+    assert_eq!(proof, snapshots[3]);
+
+    let mut recent = history.recent(2);
+    assert_eq!((recent.len(), recent.position(), recent.undos()), (2, 1, 1));
+    recent.undo(&mut proof);
+    assert_eq!(proof, snapshots[2]);
+    assert!(!recent.can_undo());
+    recent.jump(&mut proof, 2);
+    assert_eq!(proof, snapshots[4]);
+
+    // Edits that can be redone go once there is no room for them
+    let mut proof = snapshots[3].clone();
+    history.jump(&mut proof, 0);
+    assert_eq!(proof, snapshots[0]);
+    let recent = history.recent(2);
+    assert_eq!((recent.len(), recent.position()), (2, 0));
+    assert_eq!(history.recent(10), history);
+}
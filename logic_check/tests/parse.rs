@@ -0,0 +1,44 @@
+mod common;
+
+use common::term;
+use logic_check::{parse_sequent, FitchProof, Logic, SubProof};
+
+#[test]
+fn sequents() {
+    assert_eq!(
+        parse_sequent("p → q, ¬q ⊢ ¬p"),
+        Ok((vec![term("p -> q"), term("!q")], term("!p")))
+    );
+    assert_eq!(
+        parse_sequent("p->q,~q |- ~p"),
+        parse_sequent("p → q, ¬q ⊢ ¬p")
+    );
+    assert_eq!(parse_sequent("⊢ p | !p"), Ok((vec![], term("p ∨ ¬p"))));
+}
+
+#[test]
+fn sequent_errors() {
+    assert_eq!(parse_sequent("p, q").unwrap_err().at, 4);
+    assert_eq!(parse_sequent("p q ⊢ q").unwrap_err().at, 2);
+    assert_eq!(parse_sequent("p ⊢").unwrap_err().at, 3);
+    assert_eq!(parse_sequent("p ⊢ q, r").unwrap_err().at, 5);
+    assert_eq!(parse_sequent("p, ⊢ q").unwrap_err().at, 3);
+    assert!("p ⊢ q".parse::<Logic<String>>().is_err());
+}
+
+#[test]
+fn sequent_round_trip() {
+    for s in [
+        "p → q, ¬q ⊢ ¬p",
+        "⊢ ¬p ∨ p",
+        "p ∧ (q ∨ r) ⊢ (p ∧ q) ∨ (p ∧ r)",
+    ] {
+        let (prepositions, result) = parse_sequent(s).unwrap();
+        let proof = FitchProof {
+            proof: SubProof::default(),
+            prepositions: prepositions.clone(),
+            result: result.clone().into(),
+        };
+        assert_eq!(parse_sequent(&proof.sequent()), Ok((prepositions, result)));
+    }
+}
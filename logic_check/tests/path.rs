@@ -0,0 +1,118 @@
+use logic_check::{Line, Logic, ProofPath, SelectType, SubProof};
+
+fn line(l: Logic<&'static str>) -> Line<&'static str> {
+    Line::Log(l.into(), None)
+}
+
+/// `p`, `[× ∧ q, ×]`, `¬×`
+fn proof() -> SubProof<&'static str> {
+    SubProof::new(vec![
+        line(Logic::Variable("p")),
+        Line::Sub(SubProof::new(vec![
+            line(Logic::And(Logic::Empty.into(), Logic::Variable("q").into())),
+            line(Logic::Empty),
+        ])),
+        line(Logic::Not(Logic::Empty.into())),
+    ])
+}
+
+fn path(line: &[usize], term: &[usize]) -> ProofPath {
+    ProofPath {
+        line: line.to_vec(),
+        term: term.to_vec(),
+    }
+}
+
+#[test]
+fn parent_and_child() {
+    let p = ProofPath::new(vec![1, 0]);
+    assert_eq!(p.child(1), path(&[1, 0], &[1]));
+    assert_eq!(p.child(1).parent(), Some(p.clone()));
+    assert_eq!(p.parent(), Some(ProofPath::new(vec![1])));
+    assert_eq!(ProofPath::new(vec![1]).parent(), None);
+    assert_eq!(path(&[1, 0], &[1, 0]).to_line(), p);
+}
+
+#[test]
+fn lookup() {
+    let p = proof();
+    assert_eq!(p.term(&path(&[1, 0], &[1])), Some(&Logic::Variable("q")));
+    assert_eq!(p.term(&path(&[1, 0], &[2])), None);
+    assert_eq!(p.term(&path(&[1], &[])), None);
+    assert_eq!(p.select_type(&path(&[], &[])), Some(SelectType::SubProof));
+    assert_eq!(p.select_type(&path(&[1], &[])), Some(SelectType::SubProof));
+    assert_eq!(p.select_type(&path(&[1], &[0])), None);
+    assert_eq!(p.select_type(&path(&[2], &[0])), Some(SelectType::Term));
+    assert_eq!(p.select_type(&path(&[3], &[])), None);
+}
+
+#[test]
+fn lines() {
+    let p = proof();
+    let mut lines = Vec::new();
+    let mut next = Some(ProofPath::new(vec![0]));
+    while let Some(l) = next {
+        next = p.next_line(&l);
+        lines.push(l.line);
+    }
+    assert_eq!(lines, [vec![0], vec![1, 0], vec![1, 1], vec![2]]);
+
+    assert_eq!(
+        p.prev_line(&path(&[2], &[0])),
+        Some(ProofPath::new(vec![1, 1]))
+    );
+    assert_eq!(
+        p.prev_line(&path(&[1, 0], &[])),
+        Some(ProofPath::new(vec![0]))
+    );
+    assert_eq!(p.prev_line(&path(&[0], &[])), None);
+}
+
+#[test]
+fn empty_terms() {
+    let p = proof();
+    let mut empty = Vec::new();
+    let mut next = p.first_empty();
+    while let Some(e) = next {
+        next = p.next_empty(&e);
+        empty.push(e);
+    }
+    assert_eq!(
+        empty,
+        [path(&[1, 0], &[0]), path(&[1, 1], &[]), path(&[2], &[0])]
+    );
+}
+
+#[test]
+fn visitors() {
+    let p = proof();
+    let mut sub_proofs = 0;
+    p.visit(|_, l| sub_proofs += matches!(l, Line::Sub(_)) as usize);
+    assert_eq!(sub_proofs, p.stats().sub_proofs);
+
+    let mut terms = Vec::new();
+    p.visit_terms(|path, _| terms.push(path.clone()));
+    let mut sorted = terms.clone();
+    sorted.sort();
+    assert_eq!(terms, sorted);
+    assert_eq!(terms.len(), 7);
+}
+
+#[test]
+fn terms() {
+    let p = proof();
+    let mut terms = Vec::new();
+    let mut next = p.next_term(&ProofPath::default());
+    while let Some(t) = next {
+        next = p.next_term(&t);
+        terms.push(t);
+    }
+    let mut visited = Vec::new();
+    p.visit_terms(|path, _| visited.push(path.clone()));
+    assert_eq!(terms, visited);
+
+    assert_eq!(p.prev_term(&path(&[2], &[])), Some(path(&[1, 1], &[])));
+    assert_eq!(p.prev_term(&path(&[1, 0], &[1])), Some(path(&[1, 0], &[0])));
+    assert_eq!(p.prev_term(&path(&[0], &[])), None);
+    assert_eq!(p.next_term(&path(&[2], &[0])), None);
+}
@@ -0,0 +1,278 @@
+use logic_check::{
+    entails,
+    generate::{self, Rng},
+    share, FitchProof, Instruction, Line, Logic, SubProof, Verifier,
+};
+use proptest::prelude::*;
+
+const VARIABLES: &[&str] = &["p", "q", "r", "s"];
+
+fn arb_logic() -> impl Strategy<Value = Logic<String>> {
+    let leaf = prop_oneof![
+        8 => prop::sample::select(VARIABLES).prop_map(|v| Logic::Variable(v.to_string())),
+        1 => Just(Logic::Bottom),
+        1 => Just(Logic::Empty),
+    ];
+    leaf.prop_recursive(5, 32, 2, |inner| {
+        prop_oneof![
+            (inner.clone(), inner.clone()).prop_map(|(a, b)| Logic::And(a.into(), b.into())),
+            (inner.clone(), inner.clone()).prop_map(|(a, b)| Logic::Or(a.into(), b.into())),
+            (inner.clone(), inner.clone()).prop_map(|(a, b)| Logic::Implies(a.into(), b.into())),
+            inner.prop_map(|a| Logic::Not(a.into())),
+        ]
+    })
+}
+
+/// A proof the verifier should accept, and what it was generated from.
+fn arb_proof() -> impl Strategy<Value = FitchProof<&'static str>> {
+    (any::<u64>(), 0..4usize, 1..40usize).prop_map(|(seed, premises, lines)| {
+        generate::proof(&mut Rng::new(seed), VARIABLES, premises, lines)
+    })
+}
+
+/// Paths to every line and sub proof, in order.
+fn paths<T>(proof: &SubProof<T>, prefix: &mut Vec<usize>, res: &mut Vec<Vec<usize>>) {
+    for (i, l) in proof.0.iter().enumerate() {
+        prefix.push(i);
+        res.push(prefix.clone());
+        if let Line::Sub(s) = l {
+            paths(s, prefix, res);
+        }
+        prefix.pop();
+    }
+}
+
+fn all_paths<T>(proof: &SubProof<T>) -> Vec<Vec<usize>> {
+    let mut res = Vec::new();
+    paths(proof, &mut Vec::new(), &mut res);
+    res
+}
+
+/// Replaces the term of a line, or removes a line or sub proof.
+fn mutate(proof: &mut FitchProof<&'static str>, seed: u64) -> Vec<usize> {
+    let mut rng = Rng::new(seed);
+    let path = rng.pick(&all_paths(&proof.proof)).clone();
+    match proof.proof.line_at_mut(&path).unwrap() {
+        Line::Log(l, _) if !rng.one_in(4) => *l = generate::logic(&mut rng, VARIABLES, 2).into(),
+        _ => proof.proof.remove_line(&path),
+    }
+    path
+}
+
+fn invalid_lines<T>(proof: &SubProof<T>) -> Vec<bool> {
+    let mut res = Vec::new();
+    for l in proof.0.iter() {
+        match l {
+            Line::Sub(s) => res.extend(invalid_lines(s)),
+            Line::Log(_, i) => res.push(matches!(i, Some(Instruction::Invalid))),
+        }
+    }
+    res
+}
+
+proptest! {
+    #[test]
+    fn display_parse_round_trip(l in arb_logic()) {
+        let parsed = l.display(true).parse::<Logic<String>>();
+        prop_assert_eq!(parsed, Ok(l));
+    }
+
+    #[test]
+    fn ascii_parses_like_unicode(l in arb_logic()) {
+        let ascii = l
+            .display(true)
+            .replace('∧', "&")
+            .replace('∨', "|")
+            .replace('→', "->")
+            .replace('¬', "!")
+            .replace('⊥', "#")
+            .replace('×', "?");
+        prop_assert_eq!(ascii.parse::<Logic<String>>(), Ok(l));
+    }
+
+    #[test]
+    fn generated_proofs_verify(mut proof in arb_proof()) {
+        let res = proof.verify();
+        prop_assert_eq!(res, Ok(true), "\n{}", proof);
+    }
+
+    #[test]
+    fn generated_proofs_are_sound(proof in arb_proof()) {
+        prop_assert!(entails(&proof.prepositions, &proof.result));
+    }
+
+    #[test]
+    fn accepted_proofs_are_sound(mut proof in arb_proof(), seeds in prop::collection::vec(any::<u64>(), 1..4)) {
+        for seed in seeds {
+            if proof.proof.0.is_empty() {
+                break;
+            }
+            mutate(&mut proof, seed);
+        }
+        if let Some(Line::Log(l, _)) = proof.proof.0.last() {
+            proof.result = l.clone();
+        }
+        if proof.verify() == Ok(true) {
+            prop_assert!(entails(&proof.prepositions, &proof.result), "\n{}", proof);
+        }
+    }
+
+    #[test]
+    fn incremental_matches_full(mut proof in arb_proof(), seeds in prop::collection::vec(any::<u64>(), 1..6)) {
+        let mut verifier = Verifier::new();
+        let _ = verifier.verify(&mut proof);
+        for seed in seeds {
+            if proof.proof.0.is_empty() {
+                break;
+            }
+            let path = mutate(&mut proof, seed);
+            let incremental = verifier.reverify(&mut proof, &path);
+            let mut full = proof.clone();
+            prop_assert_eq!(incremental, full.verify());
+            prop_assert_eq!(invalid_lines(&proof.proof), invalid_lines(&full.proof));
+        }
+    }
+
+    #[test]
+    fn stats_are_consistent(proof in arb_proof()) {
+        let stats = proof.stats();
+        let paths = all_paths(&proof.proof);
+        prop_assert_eq!(stats.lines, proof.proof.len());
+        prop_assert_eq!(stats.lines + stats.sub_proofs, paths.len());
+
+        let mut terms = 0;
+        for p in &paths {
+            if let Some(Line::Log(l, _)) = proof.proof.line_at(p) {
+                terms += l.size();
+            }
+        }
+        prop_assert_eq!(stats.terms, terms);
+
+        // premises, lines, a separator and the result
+        let text = proof.to_string();
+        prop_assert_eq!(text.lines().count(), proof.prepositions.len() + stats.lines + 2);
+    }
+
+    #[test]
+    fn canonical_ignores_names(proof in arb_proof(), shift in 1..4usize) {
+        let rename = |v: &&'static str| {
+            let i = VARIABLES.iter().position(|x| x == v).unwrap();
+            VARIABLES[(i + shift) % VARIABLES.len()]
+        };
+        let renamed = FitchProof {
+            proof: SubProof::default(),
+            prepositions: proof.prepositions.iter().map(|p| map(p, &rename)).collect(),
+            result: map(&proof.result, &rename).into(),
+        };
+        prop_assert!(proof.same_puzzle(&renamed));
+    }
+
+    #[test]
+    fn canonical_ignores_premise_order(proof in arb_proof(), seed in any::<u64>()) {
+        let mut shuffled = proof.clone();
+        let mut rng = Rng::new(seed);
+        for i in (1..shuffled.prepositions.len()).rev() {
+            let j = rng.below(i + 1);
+            shuffled.prepositions.swap(i, j);
+        }
+        prop_assert_eq!(proof.canonical(), shuffled.canonical());
+    }
+
+    #[test]
+    fn share_round_trip(proof in arb_proof(), l in arb_logic()) {
+        let link = share::encode(&proof, true);
+        let decoded = share::decode(&link).unwrap();
+        prop_assert_eq!(share::encode(&decoded, true), link);
+        prop_assert_eq!(decoded.to_string(), proof.to_string());
+
+        let puzzle = FitchProof { proof: SubProof::default(), prepositions: vec![l.clone()], result: l.into() };
+        prop_assert_eq!(share::decode(&share::encode(&puzzle, false)), Ok(puzzle));
+    }
+
+    #[test]
+    fn double_negation_is_equivalent(l in arb_logic()) {
+        let not_not = Logic::Not(Logic::Not(l.clone().into()).into());
+        let has_empty = l.display(true).contains('×');
+        prop_assert_eq!(l.equivalent(&not_not), !has_empty);
+    }
+}
+
+fn map<T, U>(l: &Logic<T>, f: &impl Fn(&T) -> U) -> Logic<U> {
+    match l {
+        Logic::Variable(v) => Logic::Variable(f(v)),
+        Logic::And(a, b) => Logic::And(map(a, f).into(), map(b, f).into()),
+        Logic::Implies(a, b) => Logic::Implies(map(a, f).into(), map(b, f).into()),
+        Logic::Not(a) => Logic::Not(map(a, f).into()),
+        Logic::Or(a, b) => Logic::Or(map(a, f).into(), map(b, f).into()),
+        Logic::Bottom => Logic::Bottom,
+        Logic::Empty => Logic::Empty,
+    }
+}
+
+#[test]
+fn unjustified_and_is_invalid() {
+    let mut proof = FitchProof {
+        proof: SubProof::new(vec![Line::Log(
+            Logic::And(Logic::Variable("p").into(), Logic::Variable("q").into()).into(),
+            None,
+        )]),
+        prepositions: vec![Logic::Variable("p")],
+        result: Logic::And(Logic::Variable("p").into(), Logic::Variable("q").into()).into(),
+    };
+    assert_eq!(proof.verify(), Ok(false));
+}
+
+#[test]
+fn long_lines_display() {
+    let mut l = Logic::Variable("p");
+    for _ in 0..40 {
+        l = Logic::And(l.into(), Logic::Variable("q").into());
+    }
+    let proof = FitchProof {
+        proof: SubProof::new(vec![Line::Log(l.clone().into(), None)]),
+        prepositions: Vec::new(),
+        result: l.into(),
+    };
+    assert_eq!(proof.to_string().lines().count(), 3);
+}
+
+fn var(v: &'static str) -> Logic<&'static str> {
+    Logic::Variable(v)
+}
+
+fn not(l: Logic<&'static str>) -> Logic<&'static str> {
+    Logic::Not(l.into())
+}
+
+fn log(l: Logic<&'static str>) -> Line<&'static str> {
+    Line::Log(l.into(), None)
+}
+
+#[test]
+fn not_intro_of_negation() {
+    // ¬p → ⊥ ⊢ ¬¬p, by assuming ¬p
+    let mut proof = FitchProof {
+        proof: SubProof::new(vec![
+            Line::Sub(SubProof::new(vec![log(not(var("p"))), log(Logic::Bottom)])),
+            log(not(not(var("p")))),
+        ]),
+        prepositions: vec![Logic::Implies(not(var("p")).into(), Logic::Bottom.into())],
+        result: not(not(var("p"))).into(),
+    };
+    assert_eq!(proof.verify(), Ok(true));
+}
+
+#[test]
+fn or_elim_same_sides() {
+    // p ∨ p ⊢ p
+    let mut proof = FitchProof {
+        proof: SubProof::new(vec![
+            Line::Sub(SubProof::new(vec![log(var("p"))])),
+            Line::Sub(SubProof::new(vec![log(var("p"))])),
+            log(var("p")),
+        ]),
+        prepositions: vec![Logic::Or(var("p").into(), var("p").into())],
+        result: var("p").into(),
+    };
+    assert_eq!(proof.verify(), Ok(true));
+}
@@ -0,0 +1,72 @@
+use logic_check::{
+    records::{Achievement, Solve, Summary},
+    text,
+};
+
+fn solve(day: usize, solved_on: usize, proof: &str) -> Solve<String> {
+    Solve {
+        day,
+        solved_on,
+        proof: text::parse(proof).unwrap(),
+        time: Some(90),
+        undos: 1,
+        par: None,
+    }
+}
+
+const OR: &str = "p ⊢ p ∨ q\np ∨ q";
+const LEM: &str = "⊢ ¬p ∨ p\n¬p ∨ p";
+
+#[test]
+fn only_valid_proofs_count() {
+    let summary = Summary::new(
+        [
+            solve(0, 0, OR),
+            solve(1, 1, "p ⊢ q\nq"),
+            // A second proof of the same puzzle
+            solve(0, 3, LEM),
+        ],
+        3,
+    );
+    assert_eq!(summary.solved, 1);
+    assert_eq!(summary.times, [90]);
+    assert_eq!(summary.rules, [("∨l", 1)]);
+}
+
+#[test]
+fn streaks() {
+    let days = [(0, 0), (1, 1), (2, 2), (4, 4), (5, 5), (6, 9)];
+    let solves = || days.map(|(day, on)| solve(day, on, OR));
+    let summary = Summary::new(solves(), 5);
+    assert_eq!(summary.longest_streak, 3);
+    assert_eq!(summary.current_streak, 2);
+    // Today's puzzle can still be solved
+    assert_eq!(Summary::new(solves(), 6).current_streak, 2);
+    assert_eq!(Summary::new(solves(), 7).current_streak, 0);
+}
+
+#[test]
+fn achievements() {
+    let summary = Summary::new([solve(0, 0, LEM)], 0);
+    assert_eq!(summary.achievements, [Achievement::FirstProof]);
+
+    let quick = Solve {
+        time: Some(20),
+        undos: 0,
+        par: Some(1),
+        ..solve(1, 1, OR)
+    };
+    let summary = Summary::new([solve(0, 0, LEM), quick], 1);
+    assert_eq!(
+        summary.achievements,
+        [
+            Achievement::FirstProof,
+            Achievement::Constructive,
+            Achievement::OnPar,
+            Achievement::Quick,
+            Achievement::NoUndo,
+        ]
+    );
+    assert_eq!(summary.rules, [("∨l", 1), ("LEM", 1)]);
+    assert_eq!(summary.distribution(), [1, 0, 1, 0, 0]);
+}
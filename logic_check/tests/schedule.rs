@@ -0,0 +1,95 @@
+use logic_check::{
+    find_duplicates,
+    schedule::{self, Era, Schedule},
+    FitchProof,
+};
+
+fn schedule() -> Schedule {
+    Schedule(vec![
+        Era {
+            from: 0,
+            puzzles: vec![0, 1, 2],
+        },
+        Era {
+            from: 10,
+            puzzles: vec![3, 4],
+        },
+    ])
+}
+
+#[test]
+fn days() {
+    assert_eq!(schedule::day(0), 0);
+    assert_eq!(schedule::day(schedule::START), 0);
+    assert_eq!(schedule::day(schedule::day_start(5) - 1), 4);
+    assert_eq!(schedule::day(schedule::day_start(5)), 5);
+    // 2024-08-08 at 23:59 UTC
+    assert_eq!(schedule::day(1723161540), 1);
+}
+
+/// Days are numbered like they were before there was a schedule.
+#[test]
+fn days_before_schedule() {
+    assert_eq!(schedule::ROLLOVER, 31957);
+    let old = |time: i64| ((time - 1723020757) / 86400) as usize;
+    for day in [0, 1, 2, 30, 365, 1000] {
+        let start = schedule::day_start(day);
+        for time in [start - 1, start, start + 1, start + 86399] {
+            if time >= 1723020757 {
+                assert_eq!(schedule::day(time), old(time), "{time}");
+            }
+        }
+        assert_eq!(schedule::day(start - 1), day.saturating_sub(1));
+        assert_eq!(schedule::day(start), day);
+    }
+}
+
+#[test]
+fn puzzles() {
+    let schedule = schedule();
+    let days = (0..14).map(|d| schedule.puzzle(d)).collect::<Vec<_>>();
+    let expected = [0, 1, 2, 0, 1, 2, 0, 1, 2, 0, 3, 4, 3, 4];
+    assert_eq!(days, expected.map(Some));
+}
+
+#[test]
+fn appending_keeps_earlier_days() {
+    let before = schedule();
+    let mut after = schedule();
+    after.0.push(Era {
+        from: 20,
+        puzzles: vec![5],
+    });
+    for day in 0..20 {
+        assert_eq!(before.puzzle(day), after.puzzle(day));
+    }
+}
+
+#[test]
+fn check() {
+    assert_eq!(schedule().check(5), Ok(()));
+    assert!(schedule().check(4).is_err());
+    let mut unordered = schedule();
+    unordered.0.swap(0, 1);
+    assert!(unordered.check(5).is_err());
+    assert!(Schedule(vec![]).check(5).is_err());
+}
+
+/// The schedule of the game only plays puzzles that exist.
+#[test]
+fn game_schedule() {
+    let puzzles =
+        serde_json::from_str::<Vec<FitchProof<String>>>(include_str!("../../data.json")).unwrap();
+    let schedule = serde_json::from_str::<Schedule>(include_str!("../../schedule.json")).unwrap();
+    assert_eq!(schedule.check(puzzles.len()), Ok(()));
+}
+
+/// No puzzle of the game is another one with the names or the order of the
+/// premises changed. Puzzle 8 is a copy of puzzle 1, but both have been
+/// played, so it has to stay.
+#[test]
+fn game_has_no_new_duplicates() {
+    let puzzles =
+        serde_json::from_str::<Vec<FitchProof<String>>>(include_str!("../../data.json")).unwrap();
+    assert_eq!(find_duplicates(&puzzles), [(1, 8)]);
+}
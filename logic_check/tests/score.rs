@@ -0,0 +1,59 @@
+use logic_check::{
+    score::{Scorecard, UNDOS_PER_STROKE},
+    History, Line, Logic, SubProof,
+};
+
+fn stats(lines: usize) -> logic_check::Stats {
+    let proof = SubProof::new(
+        (0..lines)
+            .map(|_| Line::Log(Logic::Variable("p").into(), None))
+            .collect(),
+    );
+    proof.stats()
+}
+
+#[test]
+fn par() {
+    let at = Scorecard::new(stats(4), 0, Some(4), None);
+    assert_eq!(at.over_par(), Some(0));
+    assert_eq!(at.to_string(), "par, 1000 points");
+
+    let over = Scorecard::new(stats(6), 0, Some(4), None);
+    assert_eq!(over.to_string(), "par +2, 800 points");
+
+    let under = Scorecard::new(stats(3), 0, Some(4), None);
+    assert_eq!(under.to_string(), "par -1, 1100 points");
+
+    let unknown = Scorecard::new(stats(3), 0, None, None);
+    assert_eq!(unknown.over_par(), None);
+    assert_eq!(unknown.to_string(), "1000 points");
+}
+
+#[test]
+fn penalties() {
+    let undos = Scorecard::new(stats(4), 2 * UNDOS_PER_STROKE + 1, Some(4), None);
+    assert_eq!(undos.strokes, 6);
+    assert_eq!(undos.over_par(), Some(2));
+
+    let slow = Scorecard::new(stats(4), 0, Some(4), Some(60));
+    assert_eq!(slow.points, 990);
+    let slower = Scorecard::new(stats(20), 0, Some(4), Some(60 * 60));
+    assert_eq!(slower.points, 0);
+}
+
+#[test]
+fn history_counts_undos() {
+    let line = |v| Line::Log(Logic::Variable(v).into(), None);
+    let a = SubProof::new(vec![line("p")]);
+    let b = SubProof::new(vec![line("p"), line("q")]);
+
+    let mut history = History::new();
+    let mut proof = b.clone();
+    history.record(&a, &b, None);
+    history.undo(&mut proof);
+    history.redo(&mut proof);
+    history.undo(&mut proof);
+    // Nothing is left to undo, so this isn't counted
+    history.undo(&mut proof);
+    assert_eq!(history.undos(), 2);
+}
@@ -0,0 +1,36 @@
+mod common;
+
+use common::{line, proof, term};
+use logic_check::{share, FitchProof, Line, SubProof};
+
+#[test]
+fn encode() {
+    assert_eq!(share::encode(&proof(), false), "-p.q,!q;!p");
+    assert_eq!(share::encode(&proof(), true), "-p.q,!q;!p;(p,q,@),!p");
+    let empty = FitchProof {
+        proof: SubProof::new(vec![Line::Sub(SubProof::default()), line("×")]),
+        prepositions: vec![],
+        result: term("pq ∨ ¬r'").into(),
+    };
+    assert_eq!(share::encode(&empty, true), ";+pq!r';(?),?");
+}
+
+#[test]
+fn decode() {
+    assert_eq!(share::decode("-p.q,!q;!p;(p,q,@),!p"), Ok(proof()));
+    let puzzle = share::decode("-p.q,!q;!p").unwrap();
+    assert_eq!(puzzle.proof, SubProof::default());
+    assert!(proof().same_puzzle(&puzzle));
+}
+
+#[test]
+fn decode_errors() {
+    assert!(share::decode("").is_err());
+    assert!(share::decode("p").is_err());
+    assert!(share::decode("p;").is_err());
+    assert!(share::decode("p;q;(p").is_err());
+    assert!(share::decode("p;q;p,").is_err());
+    assert!(share::decode("p;q;p)").is_err());
+    assert!(share::decode("*p;q").is_err());
+    assert!(share::decode("p q;q").is_err());
+}
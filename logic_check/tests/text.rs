@@ -0,0 +1,85 @@
+mod common;
+
+use common::proof;
+use logic_check::{text, Line};
+
+#[test]
+fn display_round_trip() {
+    assert_eq!(text::parse(&proof().to_string()), Ok(proof()));
+    let mut verified = proof();
+    assert_eq!(verified.verify(), Ok(true));
+    assert_eq!(text::parse(&verified.to_string()), Ok(proof()));
+}
+
+#[test]
+fn handwritten() {
+    let written = "
+        p -> q, ~q |- ~p
+        | p
+        | q
+        | #
+        ~p
+    ";
+    assert_eq!(text::parse(written), Ok(proof()));
+}
+
+#[test]
+fn sibling_sub_proofs() {
+    let written = "
+        ⊢ p ∨ ¬p → p ∨ ¬p
+        │ p ∨ ¬p      ass
+        │ │ p         ass
+        │ │ p ∨ ¬p
+        │ │ ¬p        ass
+        │ │ p ∨ ¬p
+        │ p ∨ ¬p      ∨e 1 2-3 4-5
+        p ∨ ¬p → p ∨ ¬p
+    ";
+    let proof = text::parse(written).unwrap();
+    let Line::Sub(outer) = &proof.proof.0[0] else {
+        panic!("expected a sub proof");
+    };
+    assert_eq!(outer.0.len(), 4);
+    assert!(matches!(outer.0[1], Line::Sub(_)));
+    assert!(matches!(outer.0[2], Line::Sub(_)));
+}
+
+#[test]
+fn rows() {
+    let written = "p → q, ¬q ⊢ ¬p\n  3: │ p   ass\n\n │ q\n";
+    let parsed = text::read(written).unwrap();
+    let rows = parsed
+        .rows
+        .iter()
+        .map(|r| {
+            (
+                r.line,
+                r.number,
+                r.written_number.clone(),
+                r.term.clone(),
+                r.rule.clone(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        rows,
+        vec![
+            (1, 3, Some(2..3), 9..10, Some(13..16)),
+            (3, 4, None, 5..6, None),
+        ]
+    );
+    let error = text::read("p ⊢ p\np ∧").unwrap_err();
+    assert_eq!(error.line, Some(1));
+}
+
+#[test]
+fn errors() {
+    assert_eq!(
+        text::parse("  1: p  pre\n  2: p ∧"),
+        Err("line 2: expected a term at position 4".to_string())
+    );
+    assert_eq!(
+        text::parse("p  pre"),
+        Err("the proof has no result".to_string())
+    );
+}
@@ -0,0 +1,233 @@
+//! Reads proofs written as text, laid out the way [`FitchProof`]'s `Display`
+//! prints them:
+//!
+//! ```text
+//!   1: p → q                         pre
+//!   2: p                             pre
+//!   3: q                             →e 1 2
+//! ──────────
+//!  result: q
+//! ```
+//!
+//! Line numbers and rules may be left out, as the rules are recomputed when
+//! the proof is verified. Only `pre`, which marks the premises, and `ass`,
+//! which starts a new sub proof right after another one, are used. Sub
+//! proofs are indented with one `│` or `|` per level. Instead of premises and
+//! a `result:` line, the puzzle may be given as a sequent such as
+//! `p → q, p ⊢ q` before the proof.
+
+use super::{parse_sequent, FitchProof, Line, Logic, SubProof};
+use std::{fmt::Display, ops::Range};
+
+/// The rules printed by `Display` and their names, see
+/// [`super::Instruction`].
+pub const RULES: &[(&str, &str)] = &[
+    ("pre", "Premise"),
+    ("ass", "Assumption"),
+    ("∧i", "And Introduction"),
+    ("∧l", "And Elimination, left"),
+    ("∧r", "And Elimination, right"),
+    ("∨l", "Or Introduction, left"),
+    ("∨r", "Or Introduction, right"),
+    ("∨e", "Or Elimination"),
+    ("→i", "Implication Introduction"),
+    ("→e", "Implication Elimination"),
+    ("¬i", "Not Introduction"),
+    ("¬e", "Not Elimination"),
+    ("¬¬i", "Not Not Introduction"),
+    ("¬¬e", "Not Not Elimination"),
+    ("⊥e", "Bottom Elimination"),
+    ("PBC", "Proof by Contradiction"),
+    ("LEM", "Law of Excluded Middle"),
+    ("copy", "Copy"),
+    ("🛑", "Invalid"),
+];
+
+/// A proof read from text, with where its lines are in the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Parsed {
+    pub proof: FitchProof<String>,
+    /// The premises and lines of the proof that are written out, in order.
+    pub rows: Vec<Row>,
+}
+
+/// A premise or line of the proof in the text. Ranges are in bytes within
+/// the line of the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Row {
+    /// The line of the text, counted from 0.
+    pub line: usize,
+    /// The number of the premise or line in the proof, counted from 1.
+    pub number: usize,
+    /// Where the number written before it is.
+    pub written_number: Option<Range<usize>>,
+    /// Where the term is.
+    pub term: Range<usize>,
+    /// Where the rule and its line numbers are.
+    pub rule: Option<Range<usize>>,
+}
+
+/// An error in the text, at a line counted from 0, or at the end.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Error {
+    pub line: Option<usize>,
+    pub message: String,
+}
+impl Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "line {}: {}", line + 1, self.message),
+            None => write!(f, "{}", self.message),
+        }
+    }
+}
+impl std::error::Error for Error {}
+
+/// Reads a proof, with errors prefixed by their line number.
+pub fn parse(s: &str) -> Result<FitchProof<String>, String> {
+    read(s).map(|p| p.proof).map_err(|e| e.to_string())
+}
+
+/// Reads a proof and where its lines are.
+pub fn read(s: &str) -> Result<Parsed, Error> {
+    let mut prepositions = Vec::new();
+    let mut result = None;
+    let mut rows = Vec::new();
+    // The open sub proofs, outermost first
+    let mut stack = vec![Vec::new()];
+    let mut lines = 0;
+    for (n, text) in s.lines().enumerate() {
+        let error = |message: String| Error {
+            line: Some(n),
+            message,
+        };
+        let line = text.trim();
+        if line.is_empty() || line.chars().all(|c| c == '─' || c == '-') {
+            continue;
+        }
+        if let Some(r) = line.strip_prefix("result:") {
+            result = Some(r.parse().map_err(|e| error(format!("{e}")))?);
+            continue;
+        }
+        if lines == 0 && result.is_none() && (line.contains('⊢') || line.contains("|-")) {
+            let (p, r) = parse_sequent(line).map_err(|e| error(e.to_string()))?;
+            prepositions = p;
+            result = Some(r);
+            continue;
+        }
+
+        let (depth, term, mut row) = row(text).map_err(error)?;
+        row.line = n;
+        let rule = row
+            .rule
+            .clone()
+            .and_then(|r| text[r].split_whitespace().next());
+        if rule == Some("pre") && depth == 0 && lines == 0 {
+            prepositions.push(term);
+            row.number = prepositions.len();
+            rows.push(row);
+            continue;
+        }
+        lines += 1;
+        // Premises come first, so they have all been read by now
+        row.number = prepositions.len() + lines;
+        rows.push(row);
+        while stack.len() > depth + 1 {
+            close(&mut stack);
+        }
+        if rule == Some("ass") && depth > 0 && stack.len() == depth + 1 {
+            close(&mut stack);
+        }
+        while stack.len() < depth + 1 {
+            stack.push(Vec::new());
+        }
+        stack.last_mut().unwrap().push(Line::Log(term.into(), None));
+    }
+    while stack.len() > 1 {
+        close(&mut stack);
+    }
+
+    let Some(result) = result else {
+        return Err(Error {
+            line: None,
+            message: "the proof has no result".to_string(),
+        });
+    };
+    let lines = stack.pop().unwrap();
+    Ok(Parsed {
+        proof: FitchProof {
+            proof: if lines.is_empty() {
+                SubProof::default()
+            } else {
+                SubProof::new(lines)
+            },
+            prepositions,
+            result: result.into(),
+        },
+        rows,
+    })
+}
+
+fn close(stack: &mut Vec<Vec<Line<String>>>) {
+    let lines = stack.pop().unwrap();
+    if !lines.is_empty() {
+        stack
+            .last_mut()
+            .unwrap()
+            .push(Line::Sub(SubProof::new(lines)));
+    }
+}
+
+/// The depth and term of a line of the proof, and where its parts are. The
+/// line and number of the row are left for the caller.
+fn row(line: &str) -> Result<(usize, Logic<String>, Row), String> {
+    // Byte offsets into `line` of its sub slices
+    let at = |s: &str| s.as_ptr() as usize - line.as_ptr() as usize;
+    let (written_number, mut rest) = match line.split_once(':') {
+        Some((n, rest)) if n.trim().parse::<usize>().is_ok() => {
+            let n = n.trim();
+            (Some(at(n)..at(n) + n.len()), rest)
+        }
+        _ => (None, line),
+    };
+    let mut depth = 0;
+    loop {
+        rest = rest.trim_start();
+        match rest.strip_prefix(['│', '|']) {
+            Some(r) => {
+                rest = r;
+                depth += 1;
+            }
+            None => break,
+        }
+    }
+
+    // The rule is the first word naming one that is followed only by line
+    // numbers, so that variables may share names with rules
+    let words = rest.split_whitespace().collect::<Vec<_>>();
+    let split = (1..words.len())
+        .find(|&k| {
+            RULES.iter().any(|(r, _)| *r == words[k])
+                && words[k + 1..]
+                    .iter()
+                    .all(|w| w.split('-').all(|n| n.parse::<usize>().is_ok()))
+        })
+        .unwrap_or(words.len());
+    let term = words[..split]
+        .join(" ")
+        .parse()
+        .map_err(|e| format!("{e}"))?;
+    let end = |w: &str| at(w) + w.len();
+    let row = Row {
+        line: 0,
+        number: 0,
+        written_number,
+        term: match words[..split] {
+            [first, .., last] => at(first)..end(last),
+            [only] => at(only)..end(only),
+            [] => at(rest)..at(rest),
+        },
+        rule: (split < words.len()).then(|| at(words[split])..end(words[words.len() - 1])),
+    };
+    Ok((depth, term, row))
+}
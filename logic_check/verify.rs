@@ -1,362 +1,525 @@
-use super::{FitchProof, Instruction, Line, Logic, SubProof};
-use itertools::Itertools;
+use super::{Arena, FitchProof, Instruction, Line, Logic, Node, SubProof, TermId};
 use std::{
     collections::HashMap,
     fmt::{Debug, Display},
     hash::Hash,
 };
 
-#[derive(Clone)]
-struct State<T> {
+/// A proven term, or a sub proof going from the first term to the second.
+type Key = (TermId, Option<TermId>);
+
+#[derive(Debug, Default)]
+struct State {
     can_assume: bool,
-    #[allow(clippy::type_complexity)]
-    symbols: HashMap<(Logic<T>, Option<Logic<T>>), (usize, usize)>,
+    symbols: HashMap<Key, (usize, usize)>,
 }
-impl<T> State<T> {
+impl State {
     pub fn can_assume(mut self) -> Self {
         self.can_assume = true;
         self
     }
 }
-impl<T> Default for State<T> {
-    fn default() -> Self {
+
+/// A change to the scope stack, recorded so that it can be rolled back.
+#[derive(Debug)]
+enum Undo {
+    Insert(Key, Option<(usize, usize)>),
+    CanAssume(bool),
+    Push,
+    Pop(State),
+}
+
+/// Everything that is in scope while checking a line. Sub proofs push a new
+/// state and pop it again when they end, so nothing is ever copied.
+#[derive(Debug)]
+struct Checker<T> {
+    arena: Arena<T>,
+    stack: Vec<State>,
+    trail: Vec<Undo>,
+}
+
+impl<T: Clone + Hash + Eq> Checker<T> {
+    fn new() -> Self {
         Self {
-            can_assume: false,
-            symbols: HashMap::new(),
+            arena: Arena::new(),
+            stack: vec![State::default()],
+            trail: Vec::new(),
         }
     }
-}
 
-fn find_impl_elim<T: Hash + Eq + PartialEq + Clone>(
-    nk: &Logic<T>,
-    state: &[State<T>],
-) -> Option<(usize, usize)> {
-    let mut valid_impls = Vec::new();
-    for s in &state.iter().rev().collect::<Vec<_>>() {
-        for ((k, s), (r, _)) in s.symbols.iter() {
-            if s.is_none() {
-                if let Logic::Implies(a, b) = &k {
-                    if **b == *nk {
-                        valid_impls.push((a, r));
-                    }
-                }
+    fn find_symbol(&self, n: Key) -> Option<(usize, usize)> {
+        for s in self.stack.iter().rev() {
+            if let Some(p) = s.symbols.get(&n) {
+                return Some(*p);
             }
         }
+        None
     }
 
-    for (left, r) in valid_impls {
-        if let Some((_, (p, _))) = find_symbol(&(*left.clone(), None), state) {
-            return Some((p, *r));
-        }
+    /// Finds a term that has been proven, built from the given node.
+    /// If the node has never been interned it can't have been proven.
+    fn find_node(&self, node: Node<T>) -> Option<(usize, usize)> {
+        self.arena
+            .get(&node)
+            .and_then(|id| self.find_symbol((id, None)))
     }
-    None
-}
-#[allow(clippy::type_complexity)]
-fn find_or_elim<T: Hash + Eq + PartialEq>(
-    nk: &Logic<T>,
-    state: &[State<T>],
-) -> Option<(
-    usize, // or
-    usize, // left or sub proof start
-    usize, // left or sub proof end
-    usize, // right or sub proof start
-    usize, // right or sub proof start
-)> {
-    let mut valid_subs = Vec::new();
-    let mut ors = Vec::new();
-    for s in &state.iter().rev().collect::<Vec<_>>() {
-        for ((k, r), (p1, p2)) in s.symbols.iter() {
-            if let Some(r) = r {
-                if r == nk {
-                    valid_subs.push(((k, r), (p1, p2)))
+
+    /// Every proven term in scope and its line. The finders below pick the
+    /// smallest match, as the order of the symbols depends on their history,
+    /// which differs between a full and an incremental check.
+    fn facts(&self) -> impl Iterator<Item = (TermId, usize)> + '_ {
+        self.stack.iter().flat_map(|s| {
+            s.symbols
+                .iter()
+                .filter(|((_, o), _)| o.is_none())
+                .map(|((k, _), (p, _))| (*k, *p))
+        })
+    }
+
+    fn find_impl_elim(&self, nk: TermId) -> Option<(usize, usize)> {
+        self.facts()
+            .filter_map(|(k, r)| match self.arena.node(k) {
+                Node::Implies(a, b) if *b == nk => {
+                    self.find_symbol((*a, None)).map(|(p, _)| (p, r))
                 }
-            } else if let Logic::Or(a, b) = &k {
-                ors.push((p1, a, b))
-            }
-        }
+                _ => None,
+            })
+            .min()
     }
 
-    for v in valid_subs.iter().permutations(2) {
-        let [((a, _), (a_start, a_end)), ((b, _), (b_start, b_end))] = &v[..] else {
-            unreachable!()
-        };
+    #[allow(clippy::type_complexity)]
+    fn find_or_elim(
+        &self,
+        nk: TermId,
+    ) -> Option<(
+        usize, // or
+        usize, // left or sub proof start
+        usize, // left or sub proof end
+        usize, // right or sub proof start
+        usize, // right or sub proof start
+    )> {
+        self.facts()
+            .filter_map(|(k, p)| {
+                let Node::Or(a, b) = self.arena.node(k) else {
+                    return None;
+                };
+                let (a_start, a_end) = self.find_symbol((*a, Some(nk)))?;
+                let (b_start, b_end) = self.find_symbol((*b, Some(nk)))?;
+                Some((p, a_start, a_end, b_start, b_end))
+            })
+            .min()
+    }
+
+    fn find_symbol_in_and(&self, nk: TermId) -> Option<(bool, usize)> {
+        self.facts()
+            .filter_map(|(k, p)| match self.arena.node(k) {
+                Node::And(lk, _) if nk == *lk => Some((true, p)),
+                Node::And(_, lk) if nk == *lk => Some((false, p)),
+                _ => None,
+            })
+            .min_by_key(|(left, p)| (*p, !left))
+    }
+
+    fn find_not_elim(&self) -> Option<(usize, usize)> {
+        self.facts()
+            .filter_map(|(k, p)| self.find_node(Node::Not(k)).map(|(n, _)| (p, n)))
+            .min()
+    }
+
+    fn insert(&mut self, key: Key, pos: (usize, usize)) {
+        let old = self.stack.last_mut().unwrap().symbols.insert(key, pos);
+        self.trail.push(Undo::Insert(key, old));
+    }
+
+    fn set_can_assume(&mut self, can_assume: bool) {
+        let state = self.stack.last_mut().unwrap();
+        let old = std::mem::replace(&mut state.can_assume, can_assume);
+        self.trail.push(Undo::CanAssume(old));
+    }
+
+    fn push(&mut self, state: State) {
+        self.stack.push(state);
+        self.trail.push(Undo::Push);
+    }
 
-        for (or_pos, oa, ob) in &ors {
-            if **a == ***oa && **b == ***ob {
-                return Some((**or_pos, **a_start, **a_end, **b_start, **b_end));
+    fn pop(&mut self) {
+        let state = self.stack.pop().unwrap();
+        self.trail.push(Undo::Pop(state));
+    }
+
+    /// Undoes every change made after the trail had the given length.
+    fn rollback(&mut self, len: usize) {
+        while self.trail.len() > len {
+            match self.trail.pop().unwrap() {
+                Undo::Insert(key, old) => {
+                    let symbols = &mut self.stack.last_mut().unwrap().symbols;
+                    match old {
+                        Some(pos) => symbols.insert(key, pos),
+                        None => symbols.remove(&key),
+                    };
+                }
+                Undo::CanAssume(old) => self.stack.last_mut().unwrap().can_assume = old,
+                Undo::Push => {
+                    self.stack.pop();
+                }
+                Undo::Pop(state) => self.stack.push(state),
             }
         }
     }
 
-    None
-}
-fn find_symbol_in_and<T: Hash + Eq + PartialEq>(
-    nk: &Logic<T>,
-    state: &[State<T>],
-) -> Option<(bool, usize)> {
-    for s in state.iter().rev() {
-        for ((k, o), (p, _)) in s.symbols.iter() {
-            if o.is_some() {
-                continue;
-            }
-            match &k {
-                Logic::And(lk, _) if *nk == **lk => return Some((true, *p)),
-                Logic::And(_, lk) if *nk == **lk => return Some((false, *p)),
-                _ => continue,
+    /// Finds the rule that justifies the term, given everything in scope.
+    fn justify(&mut self, l: TermId) -> Result<Instruction, String>
+    where
+        T: Display,
+    {
+        let bottom = self.arena.insert(Node::Bottom);
+        let not_l = self.arena.get(&Node::Not(l));
+        let inst = if matches!(self.arena.node(l), Node::Empty) {
+            Instruction::Invalid
+        }
+        // not not elim
+        else if let Some((index, _)) = not_l.and_then(|n| self.find_node(Node::Not(n))) {
+            Instruction::NotNotElim(index)
+        }
+        // copy
+        else if let Some((index, _)) = self.find_symbol((l, None)) {
+            Instruction::Copy(index)
+        }
+        // Impl elim
+        else if let Some((a, b)) = self.find_impl_elim(l) {
+            Instruction::ImplElim(a, b)
+        }
+        // Bottom elim
+        else if let Some((index, _)) = self.find_symbol((bottom, None)) {
+            Instruction::BottomElim(index)
+        }
+        // and elim
+        else if let Some((left, index)) = self.find_symbol_in_and(l) {
+            if left {
+                Instruction::AndElimLeft(index)
+            } else {
+                Instruction::AndElimRight(index)
             }
         }
-    }
-    None
-}
-#[allow(clippy::type_complexity)]
-fn find_symbol<'a, T: Hash + Eq + PartialEq>(
-    n: &(Logic<T>, Option<Logic<T>>),
-    state: &'a [State<T>],
-) -> Option<(&'a (Logic<T>, Option<Logic<T>>), (usize, usize))> {
-    for s in state.iter().rev() {
-        if let Some((r, p)) = s.symbols.get_key_value(n) {
-            return Some((r, *p));
+        // or elim
+        else if let Some((o, a_start, a_end, b_start, b_end)) = self.find_or_elim(l) {
+            Instruction::OrElim(o, a_start..=a_end, b_start..=b_end)
         }
-    }
-    None
-}
-
-impl<T: Clone + Hash + Eq + Debug + Display> SubProof<T> {
-    #[allow(clippy::type_complexity)]
-    fn verify(
-        &mut self,
-        index: &mut usize,
-        mut stack: Vec<State<T>>,
-    ) -> Result<(Option<(Logic<T>, usize)>, Option<(Logic<T>, usize)>), String> {
-        let mut first = None;
-        let mut last = None;
-        let mut error_log = String::new();
-        let proof_len = self.0.len();
-        for (i, line) in self.0.iter_mut().enumerate() {
-            let is_first = i == 0;
-            match line {
-                Line::Sub(s) => {
-                    stack.last_mut().unwrap().can_assume = false;
-                    let mut new_stack = stack.clone();
-                    new_stack.push(State::default().can_assume());
-                    let (f, l) = s.verify(index, new_stack)?;
-                    if let Some(((f, fp), (l, lp))) = f.and_then(|f| l.map(|l| (f, l))) {
-                        let last = stack.last_mut().unwrap();
-                        last.symbols.insert((f, Some(l)), (fp, lp));
+        // PBC
+        else if let Some((a, b)) = not_l.and_then(|n| self.find_symbol((n, Some(bottom)))) {
+            Instruction::Pbc(a..=b)
+        } else {
+            match self.arena.node(l).clone() {
+                // Not Not intro
+                Node::Not(n) if matches!(self.arena.node(n), Node::Not(_)) => {
+                    let Node::Not(a) = self.arena.node(n) else {
+                        unreachable!()
+                    };
+                    if let Some((index, _)) = self.find_symbol((*a, None)) {
+                        Instruction::NotNotIntro(index)
+                    } else if let Some((start, end)) = self.find_symbol((n, Some(bottom))) {
+                        Instruction::NotIntro(start..=end)
+                    } else {
+                        Instruction::Invalid
                     }
                 }
-                Line::Log(l, t) => {
-                    *index += 1;
-                    // println!("Checking: {}\t| State: {stack:?}", l.display(true));
-                    if first.is_none() {
-                        first = Some((l.as_ref().clone(), *index));
-                    }
-                    if last.is_none() && i + 1 == proof_len {
-                        last = Some((l.as_ref().clone(), *index));
-                    }
-
-                    if is_first && stack.last_mut().unwrap().can_assume {
-                        *t = Some(Instruction::Assumption);
-                        stack
-                            .last_mut()
-                            .unwrap()
-                            .symbols
-                            .insert(((**l).clone(), None), (*index, 0));
-                        continue;
-                    }
-
-                    // not not elim
-                    if matches!(**l, Logic::Empty) {
-                        *t = Some(Instruction::Invalid);
-                    } else if let Some(((_, _), (index, _))) =
-                        find_symbol(&(Logic::Not(Logic::Not(l.clone()).into()), None), &stack)
-                    {
-                        *t = Some(Instruction::NotNotElim(index));
-                    }
-                    // copy
-                    else if let Some((_, (index, _))) = find_symbol(&(*l.clone(), None), &stack) {
-                        *t = Some(Instruction::Copy(index));
-                    }
-                    // Impl elim
-                    else if let Some((a, b)) = find_impl_elim(&*l, &stack) {
-                        *t = Some(Instruction::ImplElim(a, b));
+                // Not intro
+                Node::Not(a) => {
+                    if let Some((a_index, b_index)) = self.find_symbol((a, Some(bottom))) {
+                        Instruction::NotIntro(a_index..=b_index)
+                    } else {
+                        Instruction::Invalid
                     }
-                    // Bottom elim
-                    else if let Some((_, (index, _))) =
-                        find_symbol(&(Logic::Bottom, None), &stack)
+                }
+                // And intro
+                Node::And(a, b) => {
+                    if let Some((na, nb)) = self
+                        .find_symbol((a, None))
+                        .and_then(|(a, _)| self.find_symbol((b, None)).map(|(b, _)| (a, b)))
                     {
-                        *t = Some(Instruction::BottomElim(index));
+                        Instruction::AndIntro(na, nb)
+                    } else {
+                        Instruction::Invalid
                     }
-                    // and elim
-                    else if let Some((left, index)) = find_symbol_in_and(&*l, &stack) {
-                        if left {
-                            *t = Some(Instruction::AndElimLeft(index))
-                        } else {
-                            *t = Some(Instruction::AndElimRight(index))
-                        }
+                }
+                // Impl introduction
+                Node::Implies(a, b) => {
+                    if let Some((a, b)) = self.find_symbol((a, Some(b))) {
+                        Instruction::ImplIntro(a..=b)
+                    } else {
+                        Instruction::Invalid
                     }
-                    // or elim
-                    else if let Some((o, a_start, a_end, b_start, b_end)) =
-                        find_or_elim(&*l.clone(), &stack)
-                    {
-                        *t = Some(Instruction::OrElim(o, a_start..=a_end, b_start..=b_end))
+                }
+                // Or introduction
+                Node::Or(a, b) => {
+                    if self.arena.get(&Node::Not(b)) == Some(a) {
+                        // LEM
+                        Instruction::Lem
+                    } else if let Some((p, _)) = self.find_symbol((a, None)) {
+                        Instruction::OrIntroLeft(p)
+                    } else if let Some((p, _)) = self.find_symbol((b, None)) {
+                        Instruction::OrIntroRight(p)
+                    } else {
+                        Instruction::Invalid
                     }
-                    // PBC
-                    else if let Some((_, (a, b))) =
-                        find_symbol(&(Logic::Not(l.clone()), Some(Logic::Bottom)), &stack)
-                    {
-                        *t = Some(Instruction::Pbc(a..=b));
+                }
+                // Not elimination
+                Node::Bottom => {
+                    if let Some((p_term, n_term)) = self.find_not_elim() {
+                        Instruction::NotElim(p_term, n_term)
                     } else {
-                        match &mut **l {
-                            // Not Not intro
-                            Logic::Not(a) if matches!(&**a, Logic::Not(_)) => {
-                                let Logic::Not(a) = &**a else { unreachable!() };
-                                if let Some((_, (index, _))) =
-                                    find_symbol(&(*a.clone(), None), &stack)
-                                {
-                                    *t = Some(Instruction::NotNotIntro(index));
-                                } else {
-                                    *t = Some(Instruction::Invalid);
-                                }
-                            }
-                            // Not intro
-                            Logic::Not(a) => {
-                                if let Some((_, (a_index, b_index))) =
-                                    find_symbol(&((**a).clone(), Some(Logic::Bottom)), &stack)
-                                {
-                                    *t = Some(Instruction::NotIntro(a_index..=b_index));
-                                } else {
-                                    *t = Some(Instruction::Invalid);
-                                }
-                            }
-                            // And intro
-                            Logic::And(a, b) => {
-                                if let Some((na, nb)) = find_symbol(&(*a.clone(), None), &stack)
-                                    .and_then(|(_, (a, _))| {
-                                        find_symbol(&(*b.clone(), None), &stack)
-                                            .map(|(_, (b, _))| (a, b))
-                                    })
-                                {
-                                    *t = Some(Instruction::AndIntro(na, nb));
-                                }
-                            }
-                            // Impl introduction
-                            Logic::Implies(a, b) => {
-                                if let Some((_, (a, b))) =
-                                    find_symbol(&((**a).clone(), Some((**b).clone())), &stack)
-                                {
-                                    *t = Some(Instruction::ImplIntro(a..=b));
-                                } else {
-                                    *t = Some(Instruction::Invalid);
-                                }
-                            }
-                            // Or introduction
-                            Logic::Or(a, b) => {
-                                if **a == Logic::Not((*b).clone()) {
-                                    // LEM
-                                    *t = Some(Instruction::Lem);
-                                } else {
-                                    *t = Some(
-                                        if let Some((_, (p, _))) =
-                                            find_symbol(&((**a).clone(), None), &stack)
-                                        {
-                                            Instruction::OrIntroLeft(p)
-                                        } else if let Some((_, (p, _))) =
-                                            find_symbol(&((**b).clone(), None), &stack)
-                                        {
-                                            Instruction::OrIntroRight(p)
-                                        } else {
-                                            Instruction::Invalid
-                                        },
-                                    );
-                                }
-                            }
-                            // Not elimination
-                            Logic::Bottom => {
-                                let mut valid = false;
-                                'outer: for s in &stack {
-                                    for ((term, o), (p_term, _)) in s.symbols.iter() {
-                                        if o.is_some() {
-                                            continue;
-                                        }
-                                        if let Some((_, (n_term, _))) = find_symbol(
-                                            &(Logic::Not(term.clone().into()), None),
-                                            &stack,
-                                        ) {
-                                            *t = Some(Instruction::NotElim(*p_term, n_term));
-                                            valid = true;
-                                            break 'outer;
-                                        }
-                                    }
-                                }
-                                //stack.last_mut().unwrap()
-                                if !valid {
-                                    *t = Some(Instruction::Invalid)
-                                }
-                            }
-                            Logic::Variable(_) => {
-                                *t = Some(Instruction::Invalid);
-                            }
-                            x => {
-                                *t = Some(Instruction::Invalid);
-                                error_log.push_str(&format!(
-                                    "ERROR: Failed to find suitable rule for term \"{}\"\n",
-                                    x.display(true)
-                                ));
-                            }
-                        }
+                        Instruction::Invalid
                     }
-                    stack
-                        .last_mut()
-                        .unwrap()
-                        .symbols
-                        .insert((*l.clone(), None), (*index, 0));
+                }
+                Node::Variable(_) => Instruction::Invalid,
+                Node::Empty => {
+                    return Err(format!(
+                        "ERROR: Failed to find suitable rule for term \"{}\"\n",
+                        self.arena.to_logic(l).display(true)
+                    ))
                 }
             }
-        }
+        };
+        Ok(inst)
+    }
+}
 
-        if error_log.is_empty() {
-            Ok((first, last))
-        } else {
-            Err(error_log)
+/// The first and the last line of a sub proof that is being checked.
+#[derive(Debug, Clone, Default)]
+struct Frame {
+    first: Option<(TermId, usize)>,
+    last: Option<(TermId, usize)>,
+}
+
+/// The verifier state right before a line, or the start or end of a sub proof.
+#[derive(Debug)]
+struct Checkpoint {
+    path: Vec<usize>,
+    trail: usize,
+    index: usize,
+    frames: Vec<Frame>,
+    errors: usize,
+    invalid: usize,
+}
+
+/// A verifier that remembers its results, so that after an edit only the
+/// lines at or after the edit have to be checked again.
+///
+/// The proof is checked line by line in order, entering and leaving sub
+/// proofs as steps of their own. Before every step a checkpoint is saved,
+/// and a later call can roll back to the checkpoint before the edit and
+/// continue from there.
+#[derive(Debug)]
+pub struct Verifier<T> {
+    checker: Checker<T>,
+    prepositions: Vec<Logic<T>>,
+    frames: Vec<Frame>,
+    checkpoints: Vec<Checkpoint>,
+    index: usize,
+    errors: Vec<String>,
+    invalid: usize,
+    dirty: Option<Vec<usize>>,
+}
+impl<T: Clone + Hash + Eq + Debug + Display> Default for Verifier<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl<T: Clone + Hash + Eq + Debug + Display> Verifier<T> {
+    pub fn new() -> Self {
+        Self {
+            checker: Checker::new(),
+            prepositions: Vec::new(),
+            frames: Vec::new(),
+            checkpoints: Vec::new(),
+            index: 0,
+            errors: Vec::new(),
+            invalid: 0,
+            dirty: None,
         }
     }
 
-    fn has_invalid(&self) -> bool {
-        let mut res = false;
-        for l in &self.0 {
-            match l {
-                Line::Sub(s) => res = res || s.has_invalid(),
-                Line::Log(_, t) => {
-                    res = res
-                        || t.as_ref()
-                            .map(|a| matches!(*a, Instruction::Invalid))
-                            .unwrap_or_default()
-                }
-            }
-        }
-        res
+    /// Forgets all cached results, the next call will check everything.
+    pub fn reset(&mut self) {
+        self.checkpoints.clear();
+        self.dirty = None;
     }
-}
-impl<T: Clone + Hash + Eq + Debug + Display> FitchProof<T> {
-    pub fn verify(&mut self) -> Result<bool, String> {
-        let mut state = State::default();
-        self.prepositions.iter().enumerate().for_each(|(i, l)| {
-            state.symbols.insert((l.clone(), None), (i + 1, 0));
+
+    /// Marks the proof as changed at the given path without checking it.
+    /// The next call to [`Verifier::reverify`] will start at or before it.
+    pub fn invalidate(&mut self, edit: &[usize]) {
+        let edit = edit.to_vec();
+        self.dirty = Some(match self.dirty.take() {
+            Some(dirty) => dirty.min(edit),
+            None => edit,
         });
+    }
+
+    /// Checks the whole proof from scratch.
+    pub fn verify(&mut self, proof: &mut FitchProof<T>) -> Result<bool, String> {
+        self.reset();
+        self.reverify(proof, &[])
+    }
 
-        self.proof
-            .verify(&mut self.prepositions.len(), vec![state])?;
-        let ok = !self.proof.has_invalid()
-            && self
+    /// Checks the proof again after it was edited at `edit`, which may be
+    /// the path of a line, sub proof or term. Everything before `edit`
+    /// must be unchanged since the last call, those results are reused.
+    pub fn reverify(&mut self, proof: &mut FitchProof<T>, edit: &[usize]) -> Result<bool, String> {
+        let mut edit = line_path(&proof.proof, edit);
+        if let Some(dirty) = self.dirty.take() {
+            edit = edit.min(dirty);
+        }
+
+        let pos = if self.checkpoints.is_empty() || self.prepositions != proof.prepositions {
+            self.restart(proof);
+            vec![0]
+        } else {
+            let k = self
+                .checkpoints
+                .partition_point(|c| c.path < edit)
+                .min(self.checkpoints.len() - 1);
+            let c = &self.checkpoints[k];
+            self.checker.rollback(c.trail);
+            self.frames.clone_from(&c.frames);
+            self.index = c.index;
+            self.errors.truncate(c.errors);
+            self.invalid = c.invalid;
+            let pos = c.path.clone();
+            self.checkpoints.truncate(k);
+            pos
+        };
+        self.run(&mut proof.proof, pos);
+
+        if !self.errors.is_empty() {
+            return Err(self.errors.concat());
+        }
+        let ok = self.invalid == 0
+            && proof
                 .proof
                 .0
                 .last()
                 .map(|l| match l {
                     Line::Sub(_) => false,
-                    Line::Log(l, _) => **l == *self.result,
+                    Line::Log(l, _) => **l == *proof.result,
                 })
                 .unwrap_or_default();
         Ok(ok)
     }
+
+    fn restart(&mut self, proof: &FitchProof<T>) {
+        self.checker = Checker::new();
+        self.prepositions.clone_from(&proof.prepositions);
+        for (i, l) in self.prepositions.iter().enumerate() {
+            let l = self.checker.arena.intern(l);
+            self.checker.insert((l, None), (i + 1, 0));
+        }
+        self.frames = vec![Frame::default()];
+        self.checkpoints.clear();
+        self.index = self.prepositions.len();
+        self.errors.clear();
+        self.invalid = 0;
+    }
+
+    fn checkpoint(&mut self, pos: &[usize]) {
+        self.checkpoints.push(Checkpoint {
+            path: pos.to_vec(),
+            trail: self.checker.trail.len(),
+            index: self.index,
+            frames: self.frames.clone(),
+            errors: self.errors.len(),
+            invalid: self.invalid,
+        });
+    }
+
+    /// Checks every line from `pos` to the end of the proof.
+    fn run(&mut self, proof: &mut SubProof<T>, mut pos: Vec<usize>) {
+        loop {
+            self.checkpoint(&pos);
+            let i = *pos.last().unwrap();
+            let sub = sub_proof_at(proof, &pos[..pos.len() - 1]);
+            let Some(line) = sub.lines_mut().get_mut(i) else {
+                if pos.len() == 1 {
+                    break;
+                }
+                // End of a sub proof
+                let frame = self.frames.pop().unwrap();
+                self.checker.pop();
+                if let Some(((f, fp), (l, lp))) = frame.first.zip(frame.last) {
+                    self.checker.insert((f, Some(l)), (fp, lp));
+                }
+                pos.pop();
+                *pos.last_mut().unwrap() += 1;
+                continue;
+            };
+
+            match line {
+                Line::Sub(_) => {
+                    self.checker.set_can_assume(false);
+                    self.frames.last_mut().unwrap().last = None;
+                    self.checker.push(State::default().can_assume());
+                    self.frames.push(Frame::default());
+                    pos.push(0);
+                }
+                Line::Log(l, t) => {
+                    self.index += 1;
+                    let l = self.checker.arena.intern(l);
+                    let frame = self.frames.last_mut().unwrap();
+                    if frame.first.is_none() {
+                        frame.first = Some((l, self.index));
+                    }
+                    frame.last = Some((l, self.index));
+
+                    let inst = if i == 0 && self.checker.stack.last().unwrap().can_assume {
+                        Ok(Instruction::Assumption)
+                    } else {
+                        self.checker.justify(l)
+                    };
+                    match inst {
+                        Ok(inst) => *t = Some(inst),
+                        Err(e) => {
+                            *t = Some(Instruction::Invalid);
+                            self.errors.push(e);
+                        }
+                    }
+                    if matches!(t, Some(Instruction::Invalid)) {
+                        self.invalid += 1;
+                    }
+                    self.checker.insert((l, None), (self.index, 0));
+                    *pos.last_mut().unwrap() += 1;
+                }
+            }
+        }
+    }
+}
+
+/// Cuts off the part of a path that points into a term.
+fn line_path<T>(mut proof: &SubProof<T>, path: &[usize]) -> Vec<usize> {
+    let mut res = Vec::new();
+    for i in path {
+        res.push(*i);
+        match proof.0.get(*i) {
+            Some(Line::Sub(s)) => proof = s,
+            _ => break,
+        }
+    }
+    res
+}
+
+fn sub_proof_at<'a, T: Clone>(
+    mut proof: &'a mut SubProof<T>,
+    path: &[usize],
+) -> &'a mut SubProof<T> {
+    for i in path {
+        let Line::Sub(s) = &mut proof.lines_mut()[*i] else {
+            unreachable!()
+        };
+        proof = s;
+    }
+    proof
+}
+
+impl<T: Clone + Hash + Eq + Debug + Display> FitchProof<T> {
+    pub fn verify(&mut self) -> Result<bool, String> {
+        Verifier::new().verify(self)
+    }
 }
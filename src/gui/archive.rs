@@ -0,0 +1,43 @@
+use crate::{day_since_start, storage, ArchiveScreen, Puzzle};
+use dioxus::prelude::*;
+
+/// Every puzzle up to today's, with links to play them.
+#[component]
+pub fn Archive() -> Element {
+    let ArchiveScreen(mut archive_screen) = use_context();
+    let Puzzle(puzzle) = use_context();
+    let progress = use_resource(storage::load_progress);
+
+    let progress = progress.read();
+    let puzzles = (0..=day_since_start()).rev().map(|n| {
+        let p = progress.as_ref().and_then(|p| p.get(&n));
+        let (state, status) = match p.map(|p| (p.solved(), p.best())) {
+            Some((_, Some(best))) => ("archive-solved", format!("✓ {best}s")),
+            Some((true, None)) => ("archive-solved", "✓".to_string()),
+            Some((false, None)) => ("archive-started", "…".to_string()),
+            None => ("", String::new()),
+        };
+        let current = if n == puzzle() { "archive-current" } else { "" };
+        rsx!(a {
+            class: "archive-puzzle {state} {current}",
+            href: "?puzzle={n}",
+            div { "#{n}" }
+            div { "{status}" }
+        })
+    });
+
+    rsx!(
+        div {
+            class: "info-screen-title",
+            h1 { "Archive" }
+        }
+        div {
+            class: "archive",
+            {puzzles}
+        }
+        button {
+            onclick: move |_| archive_screen.set(false),
+            "Close"
+        }
+    )
+}
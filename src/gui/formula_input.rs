@@ -0,0 +1,59 @@
+use super::keyboard::{use_actions, Action};
+use crate::{util::intern, GlobalProof, TermSelector};
+use dioxus::prelude::*;
+use logic_check::Logic;
+
+/// A text field for typing the selected term, in ASCII or Unicode. The term
+/// is replaced on Enter.
+#[component]
+pub fn FormulaInput(typing: Signal<bool>) -> Element {
+    let TermSelector(selection) = use_context();
+    let GlobalProof(proof) = use_context();
+    let mut run = use_actions();
+    let mut text = use_signal(|| {
+        let term = selection
+            .read()
+            .as_ref()
+            .and_then(|p| proof.read().proof.term(p).cloned());
+        match term {
+            Some(Logic::Empty) | None => String::new(),
+            Some(l) => l.display(true),
+        }
+    });
+
+    let parsed = text.read().parse::<Logic<String>>();
+    let (class, feedback) = match &parsed {
+        _ if text.read().trim().is_empty() => (
+            "formula-hint",
+            "For example ¬(p ∧ q) → r or !(p & q) -> r".to_string(),
+        ),
+        Ok(l) => ("formula-preview", l.display(true)),
+        Err(e) => ("formula-error", e.to_string()),
+    };
+
+    rsx!(div {
+        class: "formula-input",
+        input {
+            r#type: "text",
+            value: "{text}",
+            placeholder: "Type a formula",
+            oninput: move |e| text.set(e.value()),
+            onkeydown: move |e| {
+                if e.key() == Key::Escape {
+                    typing.set(false);
+                }
+                if e.key() != Key::Enter {
+                    return;
+                }
+                let parsed = text.read().parse::<Logic<String>>();
+                if let Ok(l) = parsed {
+                    run(Action::Set(l.rename(&mut |v| intern(v))));
+                }
+            },
+            onmounted: move |e| async move {
+                let _ = e.set_focus(true).await;
+            },
+        }
+        div { class, "{feedback}" }
+    })
+}
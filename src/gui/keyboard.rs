@@ -1,62 +1,145 @@
+use super::FormulaInput;
 use crate::{
-    util::Droppable, ErrorField, GlobalProof, InfoScreen, StartTime, TermSelector, UndoStack,
-    WonTime,
+    leaderboard,
+    mode::Mode,
+    share,
+    storage::{self, Win},
+    use_next_puzzle,
+    util::Droppable,
+    ArchiveScreen, BestTime, BestWin, ErrorField, GameMode, GlobalProof, HelpScreen, InfoScreen,
+    ModeScreen, Paused, ProofHistory, ProofVerifier, Puzzle, Sandbox, SandboxScreen, StartTime,
+    StartToken, StatsScreen, SubmittedRank, TermSelector, TimeAttack, WonTime,
 };
 use chrono::Local;
 use dioxus::prelude::*;
-use logic_check::{Logic, SelectType};
+use logic_check::{LineEdit, Logic, ProofPath, SelectType};
 use std::fmt::Write;
 
-macro_rules! update_term {
-    ($undo_stack:expr, $check:expr, $index_map_ref:expr, $proof:expr, $exp:expr) => {
-        move |_| {
-            let c = $index_map_ref.read().as_ref().unwrap().clone();
-            $undo_stack.push($proof.write().clone(), $index_map_ref.write().clone());
-            $proof.write().proof.recurse(&c, |_| {}, $exp).drop();
-            if let Some(new_index) = $proof.write().next_select(&c) {
-                *$index_map_ref.write() = Some(new_index);
-            } else {
-                *$index_map_ref.write() = Some(c);
-            }
-            $check();
-        }
-    };
+/// Something the player can do, from a button or a key press.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Action {
+    /// Replaces the selected term.
+    Set(Logic<&'static str>),
+    /// Empties the selected term, or removes the line if it is already empty.
+    Clear,
+    Undo,
+    Redo,
+    RemoveLine,
+    MakeSubProof,
+    InsertAbove,
+    InsertBelow,
+    MoveUp,
+    MoveDown,
+    Duplicate,
+    Wrap,
+    Unwrap,
+    Split,
+    Merge,
+    SelectNext,
+    SelectPrev,
+    SelectNextLine,
+    SelectPrevLine,
+    Check,
+    Help,
+    Info,
+    Archive,
+    Sandbox,
+    Modes,
+    Stats,
+    /// Pauses or resumes the timer.
+    Pause,
+    /// Copies a link to the proof so far.
+    CopyLink,
+    /// Closes the help, or clears the selection.
+    Escape,
 }
 
-#[component]
-pub fn Keyboard() -> Element {
+impl Action {
+    /// The structural edit of the selected line the action makes, if any.
+    fn line_edit(&self) -> Option<LineEdit> {
+        Some(match self {
+            Action::InsertAbove => LineEdit::InsertAbove,
+            Action::InsertBelow => LineEdit::InsertBelow,
+            Action::MoveUp => LineEdit::MoveUp,
+            Action::MoveDown => LineEdit::MoveDown,
+            Action::Duplicate => LineEdit::Duplicate,
+            Action::Wrap => LineEdit::Wrap,
+            Action::Unwrap => LineEdit::Unwrap,
+            Action::Split => LineEdit::Split,
+            Action::Merge => LineEdit::Merge,
+            _ => return None,
+        })
+    }
+}
+
+/// Runs actions on the global proof.
+pub fn use_actions() -> impl FnMut(Action) + Copy {
     let TermSelector(mut index_map_ref) = use_context();
     let GlobalProof(mut proof) = use_context();
     let WonTime(mut won_time) = use_context();
     let ErrorField(mut error_field) = use_context();
-    let StartTime(start_time) = use_context();
+    let StartTime(mut start_time) = use_context();
     let InfoScreen(mut info_screen) = use_context();
-    let mut undo_stack = use_context::<UndoStack>();
-
-    let can_undo = !undo_stack.0.read().is_empty();
-
-    let (res, is_outer) = {
-        let mut p = proof.write();
-        let res = p.proof.recurse(
-            index_map_ref.read().as_ref()?,
-            |_| SelectType::SubProof,
-            |_| SelectType::Term,
-        )?;
-        let outer = p.proof.is_outer_term(index_map_ref.read().as_ref()?);
-        (res, outer)
-    };
+    let HelpScreen(mut help_screen) = use_context();
+    let ArchiveScreen(mut archive_screen) = use_context();
+    let SandboxScreen(mut sandbox_screen) = use_context();
+    let BestTime(mut best_time) = use_context();
+    let BestWin(mut best_win) = use_context();
+    let Puzzle(puzzle) = use_context();
+    let Sandbox(sandbox) = use_context();
+    let StartToken(token) = use_context();
+    let ProofVerifier(mut verifier) = use_context();
+    let ModeScreen(mut mode_screen) = use_context();
+    let StatsScreen(mut stats_screen) = use_context();
+    let GameMode(mode) = use_context();
+    let Paused(mut paused) = use_context();
+    let TimeAttack(mut attack) = use_context();
+    let SubmittedRank(mut rank) = use_context();
+    let mut history = use_context::<ProofHistory>();
+    let mut next_puzzle = use_next_puzzle();
 
-    let mut check = move || {
-        match proof.write().verify() {
+    let mut check = move |edit: &[usize]| {
+        let res = verifier.write().reverify(&mut proof.write(), edit);
+        match res {
             Ok(b) => {
                 if b {
-                    *won_time.write() = Some(
-                        Local::now()
-                            .signed_duration_since(start_time)
-                            .to_std()
-                            .unwrap_or_default()
-                            .as_secs() as usize,
-                    );
+                    if let Mode::TimeAttack { .. } = mode() {
+                        let next = {
+                            let mut attack = attack.write();
+                            attack.solved += 1;
+                            attack.next_puzzle()
+                        };
+                        next_puzzle(next);
+                        return;
+                    }
+                    let time = Local::now()
+                        .signed_duration_since(start_time())
+                        .to_std()
+                        .unwrap_or_default()
+                        .as_secs() as usize;
+                    *won_time.write() = Some(time);
+                    let win = Win {
+                        proof: proof.read().rename(&mut |v| v.to_string()),
+                        started: start_time().timestamp_millis(),
+                        time,
+                        undos: history.0.read().undos(),
+                        mode: mode(),
+                    };
+                    best_win.set(Some(Win::best(best_win(), win)));
+                    // Only the first timed win of a puzzle goes on the
+                    // leaderboard, and only that counts as a best time
+                    if mode() != Mode::Timed {
+                        return;
+                    }
+                    if let (false, None, Some(token)) = (sandbox(), best_time(), token()) {
+                        let code = logic_check::share::encode(&proof.read(), true);
+                        let puzzle = puzzle();
+                        spawn(async move {
+                            let res = leaderboard::submit_result(puzzle, token, time, code).await;
+                            rank.set(res.ok());
+                        });
+                    }
+                    best_time.set(storage::best_time(best_time(), Some(time)));
                 }
             }
             Err(s) => {
@@ -69,104 +152,368 @@ pub fn Keyboard() -> Element {
             }
         };
     };
-    match res {
-        SelectType::Term => rsx! (div {
-            class: "keyboard",
-            div {
-                class: "keyboard-inner",
-                button {
-                    onclick: move |_| {
-                        let pop = undo_stack.undo();
-                        if let Some((pop,index)) = pop {
-                            *proof.write() = pop;
-                            *index_map_ref.write() = index;
-                        }
-                    },
-                    disabled: !can_undo,
-                    "⎌"
-                }
-                button {
-                    onclick: move |_| {
-                        let c = index_map_ref.read().as_ref().unwrap().clone();
-                        undo_stack.push(proof.write().clone(), Some(c.clone()));
-                        proof.write().proof.remove_line(&c);
-                        *index_map_ref.write() = None;
-                        check();
-                    },
-                    disabled: !is_outer,
-                    "⌫"
-                }
-                button {
-                    onclick: move |_| {
-                        let index_map = index_map_ref.read();
-                        undo_stack.push(proof.write().clone(), index_map.clone());
-                        proof.write().proof.make_sub_proof(index_map.as_ref().unwrap());
-                        let new_map = index_map.clone().map(|mut m| {
-                            m.push(0);
-                            m
-                        });
-                        drop(index_map);
-                        *index_map_ref.write() = new_map;
-                        check();
-                    },
-                    disabled: !is_outer,
-                    "↵"
-                }
-                button {
-                    onclick: update_term!(undo_stack, check, index_map_ref, proof, |l| *l = Logic::And(
-                        Logic::Empty.into(),
-                        Logic::Empty.into(),
-                    )),
-                    "∧"
-                }
-                button {
-                    onclick: update_term!(undo_stack, check, index_map_ref, proof, |l| *l = Logic::Or(
-                        Logic::Empty.into(),
-                        Logic::Empty.into(),
-                    )),
-                    "∨"
-                }
-                button {
-                    onclick: update_term!(undo_stack, check, index_map_ref, proof, |l| *l = Logic::Implies(
-                        Logic::Empty.into(),
-                        Logic::Empty.into(),
-                    )),
-                    "→"
-                }
-                button {
-                    onclick: update_term!(undo_stack, check, index_map_ref, proof, |l| *l = Logic::Not(
-                        Logic::Empty.into(),
-                    )),
-                    "¬"
-                }
-                button {
-                    onclick: update_term!(undo_stack, check, index_map_ref, proof, |l| *l = Logic::Bottom),
-                    "⊥"
-                }
-                button {
-                    onclick: update_term!(undo_stack, check, index_map_ref, proof, |l| *l = Logic::Variable("p")),
-                    "p"
-                }
-                button {
-                    onclick: update_term!(undo_stack, check, index_map_ref, proof, |l| *l = Logic::Variable("q")),
-                    "q"
-                }
-                button {
-                    onclick: update_term!(undo_stack, check, index_map_ref, proof, |l| *l = Logic::Variable("r")),
-                    "r"
+    // Applies a structural edit to the selected line, and selects the
+    // line it returns.
+    let mut edit_line = move |c: ProofPath, edit: LineEdit| {
+        let Some(edited) = history.edit(proof, Some(c.clone()), |p| edit.apply(p, &c.line)) else {
+            return;
+        };
+        *index_map_ref.write() = Some(ProofPath::new(edited.selected));
+        check(&edited.changed);
+    };
+    let mut set_term = move |c: ProofPath, term: Logic<&'static str>| {
+        history.edit(proof, Some(c.clone()), |p| {
+            p.term_mut(&c).map(|l| *l = term).drop()
+        });
+        // Move on to the next empty term in the same line
+        let next = proof
+            .read()
+            .proof
+            .next_empty(&c)
+            .filter(|n| n.line == c.line);
+        *index_map_ref.write() = Some(next.unwrap_or_else(|| c.clone()));
+        check(&c.line);
+    };
+    let mut select = move |path: Option<ProofPath>| {
+        if path.is_some() {
+            *index_map_ref.write() = path;
+        }
+    };
+
+    move |action: Action| {
+        // Only the help can be used while it is open
+        if action == Action::Help || (action == Action::Escape && help_screen()) {
+            help_screen.set(action == Action::Help && !help_screen());
+            return;
+        }
+        if action == Action::Pause {
+            // A timed game saved while it was paused can still be resumed
+            if won_time.read().is_some() || (paused().is_none() && !mode().can_pause()) {
+                return;
+            }
+            if let Some(at) = paused() {
+                // Moving the start forward leaves the pause out of the time
+                start_time.set(start_time() + Local::now().signed_duration_since(at));
+                paused.set(None);
+            } else {
+                paused.set(Some(Local::now()));
+            }
+            return;
+        }
+        if help_screen()
+            || info_screen()
+            || archive_screen()
+            || sandbox_screen()
+            || mode_screen()
+            || stats_screen()
+            || paused().is_some()
+            || won_time.read().is_some()
+        {
+            return;
+        }
+
+        let selected = index_map_ref.read().clone();
+        let select_type = selected
+            .as_ref()
+            .and_then(|p| proof.read().proof.select_type(p));
+        let term = selected
+            .clone()
+            .filter(|_| select_type == Some(SelectType::Term));
+        let outer = term.clone().filter(|p| p.is_line());
+
+        match action {
+            Action::Set(l) => {
+                if let Some(c) = term {
+                    set_term(c, l);
                 }
-                button {
-                    onclick: update_term!(undo_stack, check, index_map_ref, proof, |l| *l = Logic::Variable("s")),
-                    "s"
+            }
+            Action::Clear => {
+                let Some(c) = term else { return };
+                let empty = matches!(proof.read().proof.term(&c), Some(Logic::Empty));
+                if !empty {
+                    set_term(c, Logic::Empty);
+                } else if c.is_line() {
+                    let prev = proof.read().proof.prev_line(&c);
+                    history.edit(proof, Some(c.clone()), |p| p.remove_line(&c.line));
+                    *index_map_ref.write() = prev;
+                    check(&c.line);
+                } else {
+                    *index_map_ref.write() = c.parent();
                 }
-                button {
-                    onclick: move |_| {
-                        info_screen.set(true);
-                    },
-                    "?"
+            }
+            Action::Undo | Action::Redo => {
+                let restored = if action == Action::Undo {
+                    history.0.write().undo(&mut proof.write().proof)
+                } else {
+                    history.0.write().redo(&mut proof.write().proof)
+                };
+                if let Some(restored) = restored {
+                    *index_map_ref.write() = restored.selection;
+                    check(&restored.edit);
                 }
             }
-        }),
-        SelectType::SubProof => rsx! { button { onclick: move |_| check(), "🔎" }},
+            Action::RemoveLine => {
+                let Some(c) = outer else { return };
+                history.edit(proof, Some(c.clone()), |p| p.remove_line(&c.line));
+                *index_map_ref.write() = None;
+                check(&c.line);
+            }
+            Action::MakeSubProof => {
+                let Some(c) = outer else { return };
+                history.edit(proof, Some(c.clone()), |p| p.make_sub_proof(&c.line));
+                let mut inner = c.line.clone();
+                inner.push(0);
+                *index_map_ref.write() = Some(ProofPath::new(inner));
+                check(&c.line);
+            }
+            Action::InsertAbove
+            | Action::InsertBelow
+            | Action::MoveUp
+            | Action::MoveDown
+            | Action::Duplicate
+            | Action::Wrap
+            | Action::Unwrap
+            | Action::Split
+            | Action::Merge => {
+                let (Some(c), Some(edit)) = (outer, action.line_edit()) else {
+                    return;
+                };
+                edit_line(c, edit);
+            }
+            Action::SelectNext | Action::SelectPrev => {
+                let proof = proof.read();
+                select(match (&selected, action) {
+                    (None, _) => proof.proof.next_term(&ProofPath::default()),
+                    (Some(c), Action::SelectNext) => proof.proof.next_term(c),
+                    (Some(c), _) => proof.proof.prev_term(c),
+                });
+            }
+            Action::SelectNextLine | Action::SelectPrevLine => {
+                let proof = proof.read();
+                select(match (&selected, action) {
+                    (None, _) => proof.proof.next_line(&ProofPath::default()),
+                    (Some(c), Action::SelectNextLine) => proof.proof.next_line(c),
+                    (Some(c), _) => proof.proof.prev_line(c),
+                });
+            }
+            Action::Check => check(&[]),
+            Action::Info => info_screen.set(true),
+            Action::Archive => archive_screen.set(true),
+            Action::Sandbox => sandbox_screen.set(true),
+            Action::Modes => mode_screen.set(true),
+            Action::Stats => stats_screen.set(true),
+            Action::CopyLink => share::copy_link(&proof.read(), true),
+            Action::Escape => *index_map_ref.write() = None,
+            Action::Help | Action::Pause => unreachable!(),
+        }
     }
 }
+
+#[component]
+pub fn Keyboard() -> Element {
+    let TermSelector(index_map_ref) = use_context();
+    let GlobalProof(proof) = use_context();
+    let history = use_context::<ProofHistory>();
+    let mut run = use_actions();
+    let mut typing = use_signal(|| false);
+
+    let can_undo = history.0.read().can_undo();
+    let can_redo = history.0.read().can_redo();
+
+    let path = index_map_ref.read().clone();
+    let selected = path
+        .as_ref()
+        .and_then(|p| proof.read().proof.select_type(p));
+    let is_outer = selected == Some(SelectType::Term) && path.as_ref().is_some_and(|p| p.is_line());
+
+    let (title, label) = if typing() {
+        ("Use the symbol keyboard", "⌗")
+    } else {
+        ("Type a formula", "✎")
+    };
+    let toggle = rsx!(button {
+        onclick: move |_| typing.set(!typing()),
+        title,
+        "{label}"
+    });
+
+    let keys = match selected {
+        // A new input for every selected term, so it starts with that term
+        Some(SelectType::Term) if typing() => rsx!(
+            {toggle}
+            FormulaInput { key: "{path:?}", typing }
+        ),
+        Some(SelectType::Term) => rsx!(
+            button {
+                onclick: move |_| run(Action::Undo),
+                disabled: !can_undo,
+                title: "Undo",
+                "⎌"
+            }
+            button {
+                onclick: move |_| run(Action::Redo),
+                disabled: !can_redo,
+                title: "Redo",
+                "↷"
+            }
+            button {
+                onclick: move |_| run(Action::RemoveLine),
+                disabled: !is_outer,
+                "⌫"
+            }
+            button {
+                onclick: move |_| run(Action::MakeSubProof),
+                disabled: !is_outer,
+                "↵"
+            }
+            button {
+                onclick: move |_| run(Action::InsertAbove),
+                disabled: !is_outer,
+                title: "Insert a line above",
+                "⤒"
+            }
+            button {
+                onclick: move |_| run(Action::InsertBelow),
+                disabled: !is_outer,
+                title: "Insert a line below",
+                "⤓"
+            }
+            button {
+                onclick: move |_| run(Action::MoveUp),
+                disabled: !is_outer,
+                title: "Move the line up",
+                "↑"
+            }
+            button {
+                onclick: move |_| run(Action::MoveDown),
+                disabled: !is_outer,
+                title: "Move the line down",
+                "↓"
+            }
+            button {
+                onclick: move |_| run(Action::Duplicate),
+                disabled: !is_outer,
+                title: "Duplicate the line",
+                "⧉"
+            }
+            button {
+                onclick: move |_| run(Action::Wrap),
+                disabled: !is_outer,
+                title: "Move the line into a new sub proof",
+                "⇥"
+            }
+            button {
+                onclick: move |_| run(Action::Unwrap),
+                disabled: !is_outer,
+                title: "Move the lines of the sub proof out of it",
+                "⇤"
+            }
+            button {
+                onclick: move |_| run(Action::Split),
+                disabled: !is_outer,
+                title: "Split the sub proof before the line",
+                "✂"
+            }
+            button {
+                onclick: move |_| run(Action::Merge),
+                disabled: !is_outer,
+                title: "Merge the sub proof with the next one",
+                "⊔"
+            }
+            button {
+                onclick: move |_| run(Action::Set(Logic::And(
+                    Logic::Empty.into(),
+                    Logic::Empty.into(),
+                ))),
+                "∧"
+            }
+            button {
+                onclick: move |_| run(Action::Set(Logic::Or(
+                    Logic::Empty.into(),
+                    Logic::Empty.into(),
+                ))),
+                "∨"
+            }
+            button {
+                onclick: move |_| run(Action::Set(Logic::Implies(
+                    Logic::Empty.into(),
+                    Logic::Empty.into(),
+                ))),
+                "→"
+            }
+            button {
+                onclick: move |_| run(Action::Set(Logic::Not(Logic::Empty.into()))),
+                "¬"
+            }
+            button {
+                onclick: move |_| run(Action::Set(Logic::Bottom)),
+                "⊥"
+            }
+            button {
+                onclick: move |_| run(Action::Set(Logic::Variable("p"))),
+                "p"
+            }
+            button {
+                onclick: move |_| run(Action::Set(Logic::Variable("q"))),
+                "q"
+            }
+            button {
+                onclick: move |_| run(Action::Set(Logic::Variable("r"))),
+                "r"
+            }
+            button {
+                onclick: move |_| run(Action::Set(Logic::Variable("s"))),
+                "s"
+            }
+            {toggle}
+        ),
+        Some(SelectType::SubProof) => rsx!(button { onclick: move |_| run(Action::Check), "🔎" }),
+        None => rsx!(),
+    };
+    // The menu is there whatever is selected, even nothing
+    let menu = rsx!(
+        button {
+            onclick: move |_| run(Action::Help),
+            title: "Keyboard shortcuts",
+            "⌨"
+        }
+        button {
+            onclick: move |_| run(Action::Archive),
+            title: "Older puzzles",
+            "📅"
+        }
+        button {
+            onclick: move |_| run(Action::Sandbox),
+            title: "Prove your own sequent",
+            "⊢"
+        }
+        button {
+            onclick: move |_| run(Action::Modes),
+            title: "Practice or time attack",
+            "⏱"
+        }
+        button {
+            onclick: move |_| run(Action::Stats),
+            title: "Statistics and achievements",
+            "📊"
+        }
+        button {
+            onclick: move |_| run(Action::CopyLink),
+            title: "Copy a link to this proof",
+            "🔗"
+        }
+        button {
+            onclick: move |_| run(Action::Info),
+            "?"
+        }
+    );
+
+    rsx!(div {
+        class: "keyboard",
+        div {
+            class: "keyboard-inner",
+            {keys}
+            {menu}
+        }
+    })
+}
@@ -1,8 +1,26 @@
+mod archive;
+pub use archive::Archive;
+
+mod formula_input;
+pub use formula_input::FormulaInput;
+
 mod info_screen;
 pub use info_screen::GuiInfoScreen;
 
 mod keyboard;
-pub use keyboard::Keyboard;
+pub use keyboard::{use_actions, Action, Keyboard};
+
+mod modes;
+pub use modes::ModeSetup;
+
+mod sandbox;
+pub use sandbox::SandboxSetup;
+
+mod shortcuts;
+pub use shortcuts::{use_shortcuts, HelpOverlay};
+
+mod stats;
+pub use stats::Statistics;
 
 mod sub_proof;
 pub use sub_proof::{SubProofComp, Term};
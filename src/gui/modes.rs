@@ -0,0 +1,68 @@
+use crate::{
+    mode::{Attack, Mode},
+    use_new_game, GameMode, GlobalProof, ModeScreen, Puzzle, Sandbox, TimeAttack,
+};
+use chrono::Local;
+use dioxus::prelude::*;
+use logic_check::{FitchProof, SubProof};
+
+/// Chooses how the game is timed, which starts the puzzle over.
+#[component]
+pub fn ModeSetup() -> Element {
+    let ModeScreen(mut mode_screen) = use_context();
+    let GameMode(mut mode) = use_context();
+    let TimeAttack(mut attack) = use_context();
+    let GlobalProof(proof) = use_context();
+    let Puzzle(puzzle) = use_context();
+    let Sandbox(mut sandbox) = use_context();
+    let mut new_game = use_new_game();
+
+    let mut start = move |new: Mode| {
+        let next = match new {
+            Mode::TimeAttack { .. } => {
+                *attack.write() = Attack::new(Local::now().timestamp_millis() as u64);
+                sandbox.set(false);
+                attack.write().next_puzzle()
+            }
+            // The generated puzzle is left for the puzzle of the day
+            _ if matches!(mode(), Mode::TimeAttack { .. }) => crate::puzzle(puzzle()),
+            _ => FitchProof {
+                proof: SubProof::default(),
+                ..proof.read().clone()
+            },
+        };
+        new_game(next);
+        mode.set(new);
+        mode_screen.set(false);
+    };
+    let class = move |m: Mode| if mode() == m { "mode-current" } else { "" };
+
+    rsx!(
+        div {
+            class: "info-screen-title",
+            h1 { "Modes" }
+        }
+        p { "Timed puzzles go on the leaderboard. Practice has no timer, and a time attack gives you new puzzles until the time runs out. Choosing a mode starts over." }
+        button {
+            class: class(Mode::Timed),
+            onclick: move |_| start(Mode::Timed),
+            "Timed"
+        }
+        button {
+            class: class(Mode::Practice),
+            onclick: move |_| start(Mode::Practice),
+            "Practice"
+        }
+        for minutes in Mode::ATTACKS {
+            button {
+                class: class(Mode::TimeAttack { minutes }),
+                onclick: move |_| start(Mode::TimeAttack { minutes }),
+                "Time attack: {minutes} min"
+            }
+        }
+        button {
+            onclick: move |_| mode_screen.set(false),
+            "Close"
+        }
+    )
+}
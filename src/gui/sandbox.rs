@@ -0,0 +1,82 @@
+use crate::{use_new_game, util::intern, BestTime, BestWin, Sandbox, SandboxScreen};
+use dioxus::prelude::*;
+use logic_check::{parse_sequent, FitchProof, SubProof};
+
+/// A text field for entering your own premises and goal, which are then
+/// proven like a puzzle but without being saved.
+#[component]
+pub fn SandboxSetup() -> Element {
+    let SandboxScreen(mut sandbox_screen) = use_context();
+    let Sandbox(mut sandbox) = use_context();
+    let BestTime(mut best_time) = use_context();
+    let BestWin(mut best_win) = use_context();
+    let mut new_game = use_new_game();
+    let mut text = use_signal(String::new);
+
+    let parsed = parse_sequent(&text.read()).map(|(prepositions, result)| FitchProof {
+        proof: SubProof::default(),
+        prepositions,
+        result: result.into(),
+    });
+    let (class, feedback) = match &parsed {
+        _ if text.read().trim().is_empty() => (
+            "formula-hint",
+            "For example p → q, q → r ⊢ p → r or p -> q, q -> r |- p -> r".to_string(),
+        ),
+        Ok(puzzle) => ("formula-preview", puzzle.sequent()),
+        Err(e) => ("formula-error", e.to_string()),
+    };
+
+    let mut start = move || {
+        let Ok((prepositions, result)) = parse_sequent(&text.read()) else {
+            return;
+        };
+        new_game(FitchProof {
+            proof: SubProof::default(),
+            prepositions: prepositions
+                .iter()
+                .map(|p| p.rename(&mut |v| intern(v)))
+                .collect(),
+            result: result.rename(&mut |v| intern(v)).into(),
+        });
+        best_time.set(None);
+        best_win.set(None);
+        sandbox.set(true);
+        sandbox_screen.set(false);
+    };
+
+    rsx!(
+        div {
+            class: "info-screen-title",
+            h1 { "Sandbox" }
+        }
+        p { "Enter the premises, separated by commas, and the goal to prove after ⊢." }
+        div {
+            class: "formula-input",
+            input {
+                r#type: "text",
+                value: "{text}",
+                placeholder: "Type a sequent",
+                oninput: move |e| text.set(e.value()),
+                onkeydown: move |e| {
+                    if e.key() == Key::Enter {
+                        start();
+                    }
+                },
+                onmounted: move |e| async move {
+                    let _ = e.set_focus(true).await;
+                },
+            }
+            div { class, "{feedback}" }
+        }
+        button {
+            disabled: parsed.is_err(),
+            onclick: move |_| start(),
+            "Start"
+        }
+        button {
+            onclick: move |_| sandbox_screen.set(false),
+            "Close"
+        }
+    )
+}
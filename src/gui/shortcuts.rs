@@ -0,0 +1,130 @@
+use super::keyboard::{use_actions, Action};
+use crate::HelpScreen;
+use dioxus::prelude::*;
+use logic_check::Logic;
+
+/// The key bindings, as shown in the help.
+const BINDINGS: &[(&str, &str)] = &[
+    ("&", "∧"),
+    ("|", "∨"),
+    (">", "→"),
+    ("!", "¬"),
+    ("#", "⊥"),
+    ("a-z", "A variable"),
+    ("← →", "Select the previous or next term"),
+    ("↑ ↓", "Select the previous or next line"),
+    ("Shift ↑ ↓", "Move the line up or down"),
+    ("Enter", "Insert a line below"),
+    ("Shift Enter", "Insert a line above"),
+    ("Tab", "Move the line into a new sub proof"),
+    ("Shift Tab", "Move the lines of the sub proof out of it"),
+    (
+        "Backspace",
+        "Clear the term, or remove the line if it is empty",
+    ),
+    ("Ctrl Z", "Undo"),
+    ("Ctrl Y", "Redo"),
+    ("Space", "Pause or resume a time attack"),
+    ("?", "Show or hide this help"),
+    ("Esc", "Clear the selection"),
+];
+
+const VARIABLES: [&str; 26] = [
+    "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p", "q", "r", "s",
+    "t", "u", "v", "w", "x", "y", "z",
+];
+
+/// Sends every key press outside of text fields to the game, and stops the
+/// browser from also handling the ones that are bound.
+const LISTENER: &str = r#"
+    document.addEventListener("keydown", (e) => {
+        if (e.altKey || e.target.matches("input, textarea")) return;
+        const ctrl = e.ctrlKey || e.metaKey;
+        const bound = ctrl
+            ? ["z", "Z", "y"]
+            : [" ", "Tab", "Enter", "Backspace", "ArrowUp", "ArrowDown", "ArrowLeft", "ArrowRight"];
+        if (bound.includes(e.key)) e.preventDefault();
+        dioxus.send([e.key, ctrl, e.shiftKey]);
+    });
+"#;
+
+/// The action bound to a key, named as by `KeyboardEvent.key` in the browser.
+fn action(key: &str, ctrl: bool, shift: bool) -> Option<Action> {
+    let empty = || Logic::Empty.into();
+    if ctrl {
+        return match key {
+            "z" if !shift => Some(Action::Undo),
+            "z" | "Z" | "y" => Some(Action::Redo),
+            _ => None,
+        };
+    }
+    let action = match key {
+        "&" => Action::Set(Logic::And(empty(), empty())),
+        "|" => Action::Set(Logic::Or(empty(), empty())),
+        ">" => Action::Set(Logic::Implies(empty(), empty())),
+        "!" => Action::Set(Logic::Not(empty())),
+        "#" => Action::Set(Logic::Bottom),
+        "ArrowLeft" => Action::SelectPrev,
+        "ArrowRight" => Action::SelectNext,
+        "ArrowUp" if shift => Action::MoveUp,
+        "ArrowDown" if shift => Action::MoveDown,
+        "ArrowUp" => Action::SelectPrevLine,
+        "ArrowDown" => Action::SelectNextLine,
+        "Enter" if shift => Action::InsertAbove,
+        "Enter" => Action::InsertBelow,
+        "Tab" if shift => Action::Unwrap,
+        "Tab" => Action::Wrap,
+        "Backspace" | "Delete" => Action::Clear,
+        " " => Action::Pause,
+        "?" => Action::Help,
+        "Escape" => Action::Escape,
+        _ => {
+            let v = VARIABLES.iter().find(|v| **v == key)?;
+            Action::Set(Logic::Variable(*v))
+        }
+    };
+    Some(action)
+}
+
+/// Listens for key presses for as long as the component is alive.
+pub fn use_shortcuts() {
+    let mut run = use_actions();
+    use_future(move || async move {
+        let mut listener = eval(LISTENER);
+        while let Ok(key) = listener.recv().await {
+            let Ok((key, ctrl, shift)) = serde_json::from_value::<(String, bool, bool)>(key) else {
+                continue;
+            };
+            if let Some(action) = action(&key, ctrl, shift) {
+                run(action);
+            }
+        }
+    });
+}
+
+#[component]
+pub fn HelpOverlay() -> Element {
+    let HelpScreen(mut help_screen) = use_context();
+    if !help_screen() {
+        return None;
+    }
+    rsx!(div {
+        class: "help-overlay",
+        div {
+            class: "help-content",
+            h2 { "Keyboard shortcuts" }
+            table {
+                for (key, description) in BINDINGS {
+                    tr {
+                        td { kbd { "{key}" } }
+                        td { "{description}" }
+                    }
+                }
+            }
+            button {
+                onclick: move |_| help_screen.set(false),
+                "Close"
+            }
+        }
+    })
+}
@@ -0,0 +1,126 @@
+use crate::{day_since_start, leaderboard, mode::Mode, puzzle, storage, StatsScreen};
+use dioxus::prelude::*;
+use logic_check::{
+    records::{Achievement, Solve, Summary, TIME_BUCKETS},
+    schedule,
+    text::RULES,
+};
+
+/// The solved puzzles of every saved game, with the pars from the server.
+async fn solves() -> Vec<Solve<String>> {
+    let mut solves = Vec::new();
+    for (day, game) in storage::load_games().await {
+        let Some(win) = game.best() else {
+            continue;
+        };
+        let puzzle = puzzle(day).rename(&mut |v| v.to_string());
+        if win.proof.prepositions != puzzle.prepositions || win.proof.result != puzzle.result {
+            continue;
+        }
+        let finished = win.started / 1000 + win.time as i64;
+        solves.push(Solve {
+            day,
+            solved_on: schedule::day(finished),
+            time: (win.mode == Mode::Timed).then_some(win.time),
+            undos: win.undos,
+            proof: win.proof,
+            par: None,
+        });
+    }
+    // All in one request, as there is a leaderboard for every day
+    let days = solves.iter().map(|s| s.day).collect();
+    if let Ok(pars) = leaderboard::pars(days).await {
+        for (solve, par) in solves.iter_mut().zip(pars) {
+            solve.par = par;
+        }
+    }
+    solves
+}
+
+/// Statistics and achievements of the puzzles solved in this browser.
+#[component]
+pub fn Statistics() -> Element {
+    let StatsScreen(mut stats_screen) = use_context();
+    let summary = use_resource(|| async { Summary::new(solves().await, day_since_start()) });
+
+    let close = rsx!(button {
+        onclick: move |_| stats_screen.set(false),
+        "Close"
+    });
+    let summary = summary.read();
+    let Some(summary) = &*summary else {
+        return rsx!(
+            div {
+                class: "info-screen-title",
+                h1 { "Statistics" }
+            }
+            {close}
+        );
+    };
+
+    let distribution = summary.distribution();
+    let most = distribution.into_iter().max().unwrap_or(1).max(1);
+    let labels = TIME_BUCKETS
+        .iter()
+        .map(|b| format!("< {b}s"))
+        .chain([format!("{}s +", TIME_BUCKETS[TIME_BUCKETS.len() - 1])]);
+    let bars = distribution.into_iter().zip(labels).map(|(count, label)| {
+        let height = 100 * count / most;
+        rsx!(div {
+            class: "distribution-bar",
+            style: "height: {height}%",
+            title: "{label}: {count}",
+        })
+    });
+    let rules = summary.rules.iter().map(|(rule, count)| {
+        let name = RULES
+            .iter()
+            .find(|(r, _)| r == rule)
+            .map_or("", |(_, name)| name);
+        rsx!(tr {
+            td { kbd { "{rule}" } }
+            td { "{name}" }
+            td { "{count}" }
+        })
+    });
+    let achievements = Achievement::ALL.into_iter().map(|a| {
+        let class = if summary.achievements.contains(&a) {
+            "achievement achievement-earned"
+        } else {
+            "achievement"
+        };
+        rsx!(div {
+            class,
+            title: a.description(),
+            div { "{a.name()}" }
+            div { class: "achievement-description", "{a.description()}" }
+        })
+    });
+
+    rsx!(
+        div {
+            class: "info-screen-title",
+            h1 { "Statistics" }
+        }
+        div {
+            class: "stats",
+            div { class: "title", "Solved: {summary.solved}" }
+            div { class: "title", "Streak: {summary.current_streak}, longest: {summary.longest_streak}" }
+            h2 { "Times" }
+            div {
+                class: "distribution",
+                {bars}
+            }
+            h2 { "Rules" }
+            table {
+                {rules}
+            }
+            h2 { "Achievements" }
+            div {
+                class: "achievements",
+                {achievements}
+            }
+        }
+        {close}
+    )
+}
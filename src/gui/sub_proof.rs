@@ -1,12 +1,12 @@
-use crate::{GlobalProof, TermSelector, UndoStack};
+use crate::{GlobalProof, ProofHistory, ProofVerifier, TermSelector};
 use dioxus::prelude::*;
-use logic_check::{Line, Logic, Ptr, SubProof};
+use logic_check::{Line, Logic, ProofPath, Ptr, SubProof};
 
 #[component]
 pub fn Term<T: 'static + PartialEq + std::fmt::Display + Clone>(
     term: Ptr<Logic<T>>,
     outer: bool,
-    index: Vec<usize>,
+    index: ProofPath,
     unselectable: bool,
     other: bool,
 ) -> Element {
@@ -24,16 +24,8 @@ pub fn Term<T: 'static + PartialEq + std::fmt::Display + Clone>(
     };
     let class = format!("{class} term-repeat-{}", if other { 1 } else { 2 });
 
-    let index0 = {
-        let mut c = index.clone();
-        c.push(0);
-        c
-    };
-    let index1 = {
-        let mut c = index.clone();
-        c.push(1);
-        c
-    };
+    let index0 = index.child(0);
+    let index1 = index.child(1);
 
     let term_gui = match &*term {
         Logic::Variable(v) => rsx!("{v}"),
@@ -96,11 +88,11 @@ pub fn SubProofComp<T: 'static + PartialEq + std::fmt::Display + Clone>(
     index_map: Vec<usize>,
     unselectable: bool,
 ) -> Element {
-    let GlobalProof(mut proof) = use_context();
+    let GlobalProof(proof) = use_context();
     let TermSelector(mut index_map_ref) = use_context();
-    let SubProof(lines) = sub_proof;
-    let mut undo_stack = use_context::<UndoStack>();
-    let lines = lines.into_iter().enumerate().map(|(i, line)| {
+    let ProofVerifier(mut verifier) = use_context();
+    let mut history = use_context::<ProofHistory>();
+    let lines = sub_proof.0.iter().cloned().enumerate().map(|(i, line)| {
         let mut c = index_map.clone();
         c.push(i);
 
@@ -126,7 +118,7 @@ pub fn SubProofComp<T: 'static + PartialEq + std::fmt::Display + Clone>(
                         pre { class: "term-line-number", "{ind}:" }
                         div {
                             class: "term-line",
-                            Term { term: l, outer: true, index: c, unselectable, other: false }
+                            Term { term: l, outer: true, index: ProofPath::new(c), unselectable, other: false }
                             div { class: "term-rule", "{a}" }
                         }
                     }
@@ -141,8 +133,8 @@ pub fn SubProofComp<T: 'static + PartialEq + std::fmt::Display + Clone>(
             ondoubleclick: {
                 let index_map = index_map.clone();
                 move |_| {
-                    undo_stack.push(proof.write().clone(), index_map_ref.write().clone());
-                    proof.write().proof.remove_line(&index_map);
+                    history.edit(proof, index_map_ref.read().clone(), |p| p.remove_line(&index_map));
+                    verifier.write().invalidate(&index_map);
                     *index_map_ref.write() = None;
                 }
             },
@@ -157,18 +149,18 @@ pub fn SubProofComp<T: 'static + PartialEq + std::fmt::Display + Clone>(
             onclick: {
                 let mut c = index_map.clone();
                 move |_| {
-                    undo_stack.push(proof.write().clone(), index_map_ref.write().clone());
-                    let pos = proof.write().proof.recurse(&c, |s| {
+                    let pos = history.edit(proof, index_map_ref.read().clone(), |p| p.recurse(&c, |s| {
                         let pos = s.0.len();
-                        s.0.push(
+                        s.lines_mut().push(
                             Line::Log(Logic::Empty.into(), None)
                         );
                         Some(pos)
-                    }, |_| None);
+                    }, |_| None));
                     if let Some(Some(pos)) = pos {
                         c.push(pos);
                     }
-                    *index_map_ref.write() = Some(c.clone());
+                    verifier.write().invalidate(&c);
+                    *index_map_ref.write() = Some(ProofPath::new(c.clone()));
                 }
             },
             "+"
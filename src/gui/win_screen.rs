@@ -1,25 +1,159 @@
 use crate::{
-    day_since_start,
     gui::{SubProofComp, Term},
-    GlobalProof,
+    leaderboard,
+    mode::{Attack, Mode},
+    share, use_new_game, ArchiveScreen, BestTime, GameMode, GlobalProof, ProofHistory, Puzzle,
+    Sandbox, SubmittedRank, TimeAttack,
 };
+use chrono::Local;
 use dioxus::prelude::*;
+use logic_check::{grid::grid, score::Scorecard, FitchProof, ProofPath, Ptr, SubProof};
 
 #[component]
 pub fn WinScreen(time: usize) -> Element {
     let GlobalProof(proof) = use_context();
+    let Puzzle(puzzle) = use_context();
+    let Sandbox(sandbox) = use_context();
+    let BestTime(best_time) = use_context();
+    let ArchiveScreen(mut archive_screen) = use_context();
+    let GameMode(mode) = use_context();
+    let TimeAttack(mut attack) = use_context();
+    let ProofHistory(history) = use_context();
+    let SubmittedRank(rank) = use_context();
+    let mut new_game = use_new_game();
+    // Fetched again once the win has been submitted, so that it is included
+    let board = use_resource(move || async move {
+        rank();
+        leaderboard::leaderboard(puzzle()).await.ok()
+    });
     let own_proof = proof.read().proof.clone();
     let pres = proof.read().prepositions.clone();
     let pres_len = pres.len();
 
+    if let Mode::TimeAttack { minutes } = mode() {
+        let solved = attack.read().solved;
+        let text = format!(
+            "⏱ I solved {solved} logiko puzzles in a {} ⏱\n\nhttps://loafey.se/logiko/",
+            mode()
+        );
+        let again = move |_| {
+            *attack.write() = Attack::new(Local::now().timestamp_millis() as u64);
+            let next = attack.write().next_puzzle();
+            new_game(next);
+        };
+        return rsx! {
+            div {
+                class: "title",
+                "Time's up!"
+            }
+            div {
+                class: "title",
+                "You solved {solved} puzzles in {minutes} minutes"
+            }
+            div {
+                class: "result-container",
+                button {
+                    onclick: move |_| {
+                        eval(&format!("navigator.clipboard.writeText({text:?})"));
+                    },
+                    "Copy Result"
+                }
+                button {
+                    onclick: again,
+                    "Play Again"
+                }
+            }
+        };
+    }
+
     let stats = proof.read().stats();
-    let win_script = format!(
-        r#"navigator.clipboard.writeText("🧩 I completed logiko#{} in {time}s 🧩\nI used {} lines, {} sub proofs and {} terms\n\nhttps://loafey.se/logiko/")"#,
-        day_since_start(),
-        stats.lines,
-        stats.sub_proofs,
-        stats.terms
-    );
+    let timed = mode() == Mode::Timed;
+    // Practice has no time to brag about
+    let how = match mode() {
+        Mode::Practice => "in practice".to_string(),
+        _ => format!("in {time}s"),
+    };
+    // Only puzzles have a par, from the proofs on the leaderboard
+    let par = match &*board.read() {
+        Some(Some(board)) if !sandbox() => board.par,
+        _ => None,
+    };
+    let scorecard = Scorecard::new(stats, history.read().undos(), par, timed.then_some(time));
+    // The shape of the proof, without the terms that would spoil it
+    let grid = grid(&proof.read().proof);
+    let text = if sandbox() {
+        format!(
+            "🧩 I proved {} {how} 🧩\nI used {} lines, {} sub proofs and {} terms\n{scorecard}\n\n{grid}",
+            proof.read().sequent(),
+            stats.lines,
+            stats.sub_proofs,
+            stats.terms
+        )
+    } else {
+        format!(
+            "🧩 I completed logiko#{} {how} 🧩\nI used {} lines, {} sub proofs and {} terms\n{scorecard}\n\n{grid}\n\nhttps://loafey.se/logiko/",
+            puzzle(),
+            stats.lines,
+            stats.sub_proofs,
+            stats.terms
+        )
+    };
+    let win_script = format!("navigator.clipboard.writeText({text:?})");
+    let won = if timed {
+        format!("You won in: {time}s")
+    } else {
+        "You solved it in practice".to_string()
+    };
+    let best = if let Some(best) = best_time().filter(|best| timed && *best < time) {
+        rsx!(div {
+            class: "title",
+            "Your best: {best}s"
+        })
+    } else {
+        rsx!()
+    };
+    // Only a win the server took has a rank
+    let rank = match (&*board.read(), rank()) {
+        (Some(Some(board)), Some(rank)) if timed && !sandbox() => {
+            let total = board.times.len().max(rank);
+            // Around 20 bars, however slow the slowest result is
+            let width = (board.times.last().copied().unwrap_or_default() / 20).max(10);
+            let distribution = board.distribution(width);
+            let most = distribution.iter().copied().max().unwrap_or(1);
+            let bars = distribution.into_iter().enumerate().map(|(n, count)| {
+                let own = if n == time / width {
+                    "distribution-own"
+                } else {
+                    ""
+                };
+                let height = 100 * count / most;
+                let title = format!("{}-{}s: {count}", n * width, (n + 1) * width);
+                rsx!(div {
+                    class: "distribution-bar {own}",
+                    style: "height: {height}%",
+                    title,
+                })
+            });
+            rsx!(
+                div {
+                    class: "title",
+                    "Rank: #{rank} of {total}"
+                }
+                div {
+                    class: "distribution",
+                    {bars}
+                }
+            )
+        }
+        _ => rsx!(),
+    };
+    let play_again = move |_: MouseEvent| {
+        let proof = proof.read().clone();
+        new_game(FitchProof {
+            proof: SubProof::default(),
+            ..proof
+        });
+    };
     let copy_text_tree = format!(r#"navigator.clipboard.writeText(`{}`)"#, proof.read());
     let copy_latex_tree = format!(
         r#"navigator.clipboard.writeText({:?})"#,
@@ -34,9 +168,23 @@ pub fn WinScreen(time: usize) -> Element {
 
         div {
             class: "title",
-            "You won in: {time}s"
+            "{won}"
         }
 
+        {best}
+
+        div {
+            class: "title",
+            "Score: {scorecard}"
+        }
+
+        pre {
+            class: "share-grid",
+            "{grid}"
+        }
+
+        {rank}
+
         div {
             class: "result-container",
             button {
@@ -60,6 +208,28 @@ pub fn WinScreen(time: usize) -> Element {
                 "Copy LaTeX Tree"
             }
 
+            button {
+                onclick: move |_| share::copy_link(&proof.read(), false),
+                title: "A link to try this puzzle",
+                "Copy Puzzle Link"
+            }
+
+            button {
+                onclick: move |_| share::copy_link(&proof.read(), true),
+                title: "A link to view your proof",
+                "Copy Proof Link"
+            }
+
+            button {
+                onclick: play_again,
+                "Play Again"
+            }
+
+            button {
+                onclick: move |_| archive_screen.set(true),
+                "Older Puzzles"
+            }
+
             div {
                 class: "sub-proof-outer",
                 for (ind, l) in pres.into_iter().enumerate() {
@@ -68,7 +238,7 @@ pub fn WinScreen(time: usize) -> Element {
                         pre { class: "term-rule", style: "padding-left: 15px", "{ind + 1}:" }
                         div {
                             class: "term-line",
-                            Term { term: Box::new(l), outer: true, index: Vec::new(), unselectable: true, other: false }
+                            Term { term: Ptr::new(l), outer: true, index: ProofPath::default(), unselectable: true, other: false }
                             div { class: "term-rule", "{logic_check::Instruction::Premise}" }
                         }
                     }
@@ -0,0 +1,358 @@
+//! The leaderboard of every puzzle, kept by the server in a JSON file per
+//! puzzle so that it runs without a database. The files are put in
+//! `$LOGIKO_DATA`, or `leaderboard/` if it isn't set.
+//!
+//! The server doesn't take the client's word for a proof. A puzzle is
+//! started by fetching a token with [`start_puzzle`], and a result is only
+//! accepted with a token for the puzzle, a proof the server verifies itself,
+//! and a time that fits with when the token was issued. The time itself is
+//! still up to the client: nothing stops anyone from fetching a new token
+//! once they know the proof, so times can be cheated.
+
+use dioxus::prelude::*;
+use logic_check::Stats;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Score {
+    pub time: usize,
+    pub stats: Stats,
+}
+
+/// The results of a puzzle, without the proofs.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Leaderboard {
+    /// The fastest results, fastest first.
+    pub top: Vec<Score>,
+    /// The time of every result, fastest first.
+    pub times: Vec<usize>,
+    /// The fewest lines of any proof, see [`logic_check::score`].
+    #[serde(default)]
+    pub par: Option<usize>,
+}
+
+impl Leaderboard {
+    /// How many results took `n * width` up to `(n + 1) * width` seconds,
+    /// for every `n` up to the slowest result.
+    pub fn distribution(&self, width: usize) -> Vec<usize> {
+        let mut buckets = Vec::new();
+        for t in &self.times {
+            let n = t / width.max(1);
+            if buckets.len() <= n {
+                buckets.resize(n + 1, 0);
+            }
+            buckets[n] += 1;
+        }
+        buckets
+    }
+}
+
+/// A token marking when the puzzle was started, to be sent along with the
+/// result.
+#[server]
+pub async fn start_puzzle(puzzle: usize) -> Result<String, ServerFnError> {
+    if puzzle > crate::day_since_start() {
+        return Err(ServerFnError::new("this puzzle isn't out yet"));
+    }
+    Ok(backend::token::issue(puzzle))
+}
+
+/// Submits a win, returning its rank among the results of the puzzle.
+/// The proof is encoded as for links.
+#[server]
+pub async fn submit_result(
+    puzzle: usize,
+    token: String,
+    time: usize,
+    proof: String,
+) -> Result<usize, ServerFnError> {
+    if puzzle > crate::day_since_start() {
+        return Err(ServerFnError::new("this puzzle isn't out yet"));
+    }
+    let stats = backend::check(puzzle, &token, time, &proof).map_err(ServerFnError::new)?;
+    backend::submit(
+        &backend::data_dir(),
+        puzzle,
+        backend::Entry {
+            score: Score { time, stats },
+            proof,
+            token,
+        },
+    )
+    .map_err(ServerFnError::new)
+}
+
+#[server]
+pub async fn leaderboard(puzzle: usize) -> Result<Leaderboard, ServerFnError> {
+    backend::leaderboard(&backend::data_dir(), puzzle).map_err(ServerFnError::new)
+}
+
+/// The par of every puzzle, in order, for when the whole leaderboards aren't
+/// needed.
+#[server]
+pub async fn pars(puzzles: Vec<usize>) -> Result<Vec<Option<usize>>, ServerFnError> {
+    let dir = backend::data_dir();
+    puzzles
+        .into_iter()
+        .map(|p| backend::leaderboard(&dir, p).map(|b| b.par))
+        .collect::<Result<_, _>>()
+        .map_err(ServerFnError::new)
+}
+
+#[cfg(feature = "server")]
+mod backend {
+    use super::{Leaderboard, Score};
+    use logic_check::{share, Stats};
+    use serde::{Deserialize, Serialize};
+    use std::{
+        fs,
+        io::ErrorKind,
+        path::{Path, PathBuf},
+        sync::Mutex,
+    };
+
+    /// How many of the fastest results are listed.
+    const TOP: usize = 10;
+
+    /// How many seconds the time of a result may differ from the time since
+    /// its token was issued, which covers the round trips to the server.
+    const SLACK: usize = 10;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct Entry {
+        pub score: Score,
+        pub proof: String,
+        /// The token the result was submitted with, so that it can't be
+        /// used twice.
+        #[serde(default)]
+        pub token: String,
+    }
+
+    /// Checks a result against its token and the puzzle, and returns the
+    /// stats of the proof.
+    pub fn check(puzzle: usize, token: &str, time: usize, proof: &str) -> Result<Stats, String> {
+        let (token_puzzle, elapsed) = token::check(token)?;
+        if token_puzzle != puzzle {
+            return Err("the token is for another puzzle".to_string());
+        }
+        if time > elapsed + SLACK || time + SLACK < elapsed {
+            return Err(format!(
+                "the time {time}s doesn't match the {elapsed}s since the puzzle was started"
+            ));
+        }
+        let mut proof = share::decode(proof)?;
+        let expected = crate::puzzle(puzzle).rename(&mut |v| v.to_string());
+        if proof.prepositions != expected.prepositions || proof.result != expected.result {
+            return Err("the proof is for another puzzle".to_string());
+        }
+        if !proof.verify()? {
+            return Err("the proof doesn't prove the result".to_string());
+        }
+        Ok(proof.stats())
+    }
+
+    /// Tokens are the puzzle, the time they were issued and an HMAC-SHA256
+    /// of both with a secret, which is `$LOGIKO_SECRET` or a random one for
+    /// every run of the server. This only proves that the server issued the
+    /// token, not who to or how often.
+    pub mod token {
+        use hmac::{Hmac, Mac};
+        use sha2::Sha256;
+        use std::{
+            collections::hash_map::RandomState,
+            hash::BuildHasher,
+            sync::OnceLock,
+            time::{SystemTime, UNIX_EPOCH},
+        };
+
+        fn secret() -> &'static str {
+            static SECRET: OnceLock<String> = OnceLock::new();
+            SECRET.get_or_init(|| {
+                std::env::var("LOGIKO_SECRET").unwrap_or_else(|_| {
+                    format!("{:x}", RandomState::new().hash_one(SystemTime::now()))
+                })
+            })
+        }
+
+        fn mac(puzzle: usize, issued: u64) -> Hmac<Sha256> {
+            let mut mac = Hmac::<Sha256>::new_from_slice(secret().as_bytes())
+                .expect("HMAC takes keys of any length");
+            mac.update(format!("{puzzle}.{issued}").as_bytes());
+            mac
+        }
+
+        fn now() -> u64 {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs()
+        }
+
+        pub fn issue(puzzle: usize) -> String {
+            issue_at(puzzle, now())
+        }
+
+        /// A token as if it was issued at `issued`, in seconds since the Unix
+        /// epoch.
+        pub fn issue_at(puzzle: usize, issued: u64) -> String {
+            let sig = mac(puzzle, issued).finalize().into_bytes();
+            let hex = sig.iter().map(|b| format!("{b:02x}")).collect::<String>();
+            format!("{puzzle}.{issued}.{hex}")
+        }
+
+        /// The puzzle of the token, and the seconds since it was issued.
+        pub fn check(token: &str) -> Result<(usize, usize), String> {
+            let invalid = || "invalid token".to_string();
+            let mut parts = token.split('.');
+            let (Some(puzzle), Some(issued), Some(sig), None) =
+                (parts.next(), parts.next(), parts.next(), parts.next())
+            else {
+                return Err(invalid());
+            };
+            let puzzle = puzzle.parse::<usize>().map_err(|_| invalid())?;
+            let issued = issued.parse::<u64>().map_err(|_| invalid())?;
+            let sig = (0..sig.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(sig.get(i..i + 2)?, 16).ok())
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(invalid)?;
+            // Compared in constant time, so the signature can't be guessed
+            // byte by byte
+            mac(puzzle, issued)
+                .verify_slice(&sig)
+                .map_err(|_| invalid())?;
+            Ok((puzzle, now().saturating_sub(issued) as usize))
+        }
+    }
+
+    /// Every request reads and rewrites a whole file, so they take turns.
+    static LOCK: Mutex<()> = Mutex::new(());
+
+    /// Where the leaderboards are kept, see the module docs.
+    pub fn data_dir() -> PathBuf {
+        std::env::var("LOGIKO_DATA")
+            .unwrap_or_else(|_| "leaderboard".to_string())
+            .into()
+    }
+
+    fn path(dir: &Path, puzzle: usize) -> PathBuf {
+        dir.join(format!("{puzzle}.json"))
+    }
+
+    fn read(dir: &Path, puzzle: usize) -> Result<Vec<Entry>, String> {
+        match fs::read_to_string(path(dir, puzzle)) {
+            Ok(json) => serde_json::from_str(&json).map_err(|e| e.to_string()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(Vec::new()),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn write(dir: &Path, puzzle: usize, entries: &[Entry]) -> Result<(), String> {
+        let path = path(dir, puzzle);
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        // Written next to the file and then moved over it, so that a crash
+        // never leaves half a file behind
+        let tmp = path.with_extension("json.tmp");
+        let json = serde_json::to_string(entries).map_err(|e| e.to_string())?;
+        fs::write(&tmp, json).map_err(|e| e.to_string())?;
+        fs::rename(tmp, path).map_err(|e| e.to_string())
+    }
+
+    pub fn submit(dir: &Path, puzzle: usize, entry: Entry) -> Result<usize, String> {
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut entries = read(dir, puzzle)?;
+        if entries.iter().any(|e| e.token == entry.token) {
+            return Err("the token has already been used".to_string());
+        }
+        let rank = entries
+            .iter()
+            .filter(|e| e.score.time < entry.score.time)
+            .count()
+            + 1;
+        entries.push(entry);
+        write(dir, puzzle, &entries)?;
+        Ok(rank)
+    }
+
+    pub fn leaderboard(dir: &Path, puzzle: usize) -> Result<Leaderboard, String> {
+        let _lock = LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        let mut scores = read(dir, puzzle)?
+            .into_iter()
+            .map(|e| e.score)
+            .collect::<Vec<_>>();
+        scores.sort_by_key(|s| s.time);
+        Ok(Leaderboard {
+            times: scores.iter().map(|s| s.time).collect(),
+            par: scores.iter().map(|s| s.stats.lines).min(),
+            top: scores.into_iter().take(TOP).collect(),
+        })
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use std::time::{SystemTime, UNIX_EPOCH};
+
+        /// A proof of `⊢ p ∨ (p → q)`, the puzzle of day 0.
+        const PROOF: &str = ";+p-p.q;+!p.p,(!p,(p,@,q),-p.q,+p-p.q),(p,+p-p.q),+p-p.q";
+
+        /// A token for the puzzle issued `ago` seconds ago.
+        fn started(puzzle: usize, ago: u64) -> String {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            token::issue_at(puzzle, now - ago)
+        }
+
+        #[test]
+        fn accepts_wins() {
+            let stats = check(0, &started(0, 60), 60, PROOF).unwrap();
+            assert_eq!(stats.lines, 10);
+            assert!(check(0, &started(0, 60), 60 - SLACK + 1, PROOF).is_ok());
+        }
+
+        #[test]
+        fn rejects_tokens() {
+            assert!(check(1, &started(0, 60), 60, PROOF).is_err());
+            assert!(check(0, "0.0.00", 60, PROOF).is_err());
+            let mut forged = started(0, 60);
+            forged.replace_range(2..3, "9");
+            assert!(check(0, &forged, 60, PROOF).is_err());
+        }
+
+        /// A time that doesn't fit with the token, such as one that leaves out
+        /// a pause.
+        #[test]
+        fn rejects_times_outside_slack() {
+            assert!(check(0, &started(0, 60), 60 - SLACK - 2, PROOF).is_err());
+            assert!(check(0, &started(0, 60), 60 + SLACK + 2, PROOF).is_err());
+            assert!(check(0, &started(0, 0), 120, PROOF).is_err());
+        }
+
+        #[test]
+        fn rejects_invalid_proofs() {
+            let token = started(0, 60);
+            assert!(check(0, &token, 60, ";+p-p.q;+p-p.q").is_err());
+            assert!(check(0, &token, 60, "p;p;p").is_err());
+            assert!(check(0, &token, 60, "not a proof").is_err());
+        }
+
+        #[test]
+        fn rejects_reused_tokens() {
+            let dir = std::env::temp_dir().join(format!("logiko-{}", std::process::id()));
+            let token = started(0, 60);
+            let stats = check(0, &token, 60, PROOF).unwrap();
+            let entry = Entry {
+                score: Score { time: 60, stats },
+                proof: PROOF.to_string(),
+                token,
+            };
+            assert_eq!(submit(&dir, 0, entry.clone()), Ok(1));
+            assert!(submit(&dir, 0, entry).is_err());
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
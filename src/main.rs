@@ -2,45 +2,76 @@
 #[macro_use]
 extern crate log;
 
-use chrono::{DateTime, Local};
+use chrono::{DateTime, Local, Utc};
 use dioxus::prelude::*;
-use gui::{GuiInfoScreen, Keyboard, SubProofComp, Term, WinScreen};
-use logic_check::{empty, FitchProof};
+use gui::{
+    use_actions, use_shortcuts, Action, Archive, GuiInfoScreen, HelpOverlay, Keyboard, ModeSetup,
+    SandboxSetup, Statistics, SubProofComp, Term, WinScreen,
+};
+use logic_check::{
+    empty,
+    schedule::{self, Schedule},
+    FitchProof, History, ProofPath, Ptr, SubProof, Verifier,
+};
+use mode::{Attack, Mode};
+use storage::{SavedGame, Win};
 mod gui;
+mod leaderboard;
+mod mode;
+mod share;
+mod storage;
 mod util;
 
 fn main() {
-    #[cfg(not(target_arch = "wasm32"))]
-    {}
-    #[cfg(target_arch = "wasm32")]
-    {
-        dioxus_logger::init(dioxus_logger::tracing::Level::INFO).expect("failed to init logger");
-        launch(app);
-    }
+    // Built with the `web` feature for the browser and `server` for the
+    // leaderboard server, which also serves the game
+    dioxus_logger::init(dioxus_logger::tracing::Level::INFO).expect("failed to init logger");
+    launch(app);
 }
 
 #[component]
 fn MainApp() -> Element {
     let GlobalProof(proof) = use_context();
     let StartTime(start_time) = use_context();
-    let WonTime(won_time) = use_context();
+    let WonTime(mut won_time) = use_context();
     let InfoScreen(info_screen) = use_context();
+    let ArchiveScreen(archive_screen) = use_context();
+    let Puzzle(puzzle) = use_context();
+    let Sandbox(sandbox) = use_context();
+    let SandboxScreen(sandbox_screen) = use_context();
+    let ModeScreen(mode_screen) = use_context();
+    let StatsScreen(stats_screen) = use_context();
+    let GameMode(mode) = use_context();
+    let Paused(paused) = use_context();
+    let TimeAttack(attack) = use_context();
     // let TermSelector(debug) = use_context();
+    use_shortcuts();
+    let mut run = use_actions();
 
     let mut elapsed = use_signal(|| {
         Local::now()
-            .signed_duration_since(start_time)
+            .signed_duration_since(start_time())
             .to_std()
             .unwrap_or_default()
     });
     use_coroutine::<(), _, _>(move |_: UnboundedReceiver<_>| async move {
         loop {
-            elapsed.set(
-                Local::now()
-                    .signed_duration_since(start_time)
-                    .to_std()
-                    .unwrap_or_default(),
-            );
+            // The clock keeps running in practice, it just isn't shown
+            if paused().is_none() && mode() != Mode::Practice {
+                elapsed.set(
+                    Local::now()
+                        .signed_duration_since(start_time())
+                        .to_std()
+                        .unwrap_or_default(),
+                );
+            }
+            // A time attack is over when its time runs out, however many
+            // puzzles were solved
+            if let Some(limit) = mode().time_limit() {
+                if won_time.read().is_none() && elapsed.read().as_secs() as usize >= limit {
+                    won_time.set(Some(limit));
+                }
+            }
             wasmtimer::tokio::sleep(std::time::Duration::from_secs(1)).await
         }
     });
@@ -49,7 +80,7 @@ fn MainApp() -> Element {
     let result = rsx!(Term {
         term: proof.read().result.clone(),
         outer: true,
-        index: Vec::new(),
+        index: ProofPath::default(),
         unselectable: true,
         other: false,
     });
@@ -60,6 +91,18 @@ fn MainApp() -> Element {
     let body = if *info_screen.read() {
         large_bottom = false;
         rsx!(GuiInfoScreen {})
+    } else if archive_screen() {
+        large_bottom = false;
+        rsx!(Archive {})
+    } else if sandbox_screen() {
+        large_bottom = false;
+        rsx!(SandboxSetup {})
+    } else if mode_screen() {
+        large_bottom = false;
+        rsx!(ModeSetup {})
+    } else if stats_screen() {
+        large_bottom = false;
+        rsx!(Statistics {})
     } else if let Some(time) = &*won_time.read() {
         large_bottom = true;
         rsx!(WinScreen { time: *time })
@@ -74,11 +117,59 @@ fn MainApp() -> Element {
         } else {
             rsx!()
         };
+        let name = if let Mode::TimeAttack { .. } = mode() {
+            format!("Time attack: {} solved", attack.read().solved)
+        } else if sandbox() {
+            "Sandbox".to_string()
+        } else {
+            format!("Puzzle: {puzzle}")
+        };
+        let secs = elapsed.read().as_secs() as usize;
+        let clock = match mode() {
+            Mode::Practice => "practice".to_string(),
+            Mode::Timed => format!("{secs}s"),
+            Mode::TimeAttack { .. } => {
+                let left = mode().time_limit().unwrap_or_default().saturating_sub(secs);
+                format!("{left}s left")
+            }
+        };
+        let pause = move |_: MouseEvent| run(Action::Pause);
+        let (title, label) = if paused().is_some() {
+            ("Resume", "▶")
+        } else {
+            ("Pause", "⏸")
+        };
+        let pause_button = rsx!(if mode().can_pause() || paused().is_some() {
+            button {
+                class: "pause",
+                onclick: pause,
+                title,
+                "{label}"
+            }
+        });
+        if paused().is_some() {
+            return rsx!(div {
+                class: "app-container app-container-info",
+                div {
+                    class: "title",
+                    "{name}, "
+                    span {"{clock}"}
+                    {pause_button}
+                }
+                div {
+                    class: "title",
+                    "Paused"
+                }
+                button { onclick: pause, "Resume" }
+                HelpOverlay {}
+            });
+        }
         rsx! {
             div {
                 class: "title",
-                "Puzzle: {day_since_start()}, "
-                span {"{elapsed.read().as_secs()}s"}
+                "{name}, "
+                span {"{clock}"}
+                {pause_button}
                 // span {" {debug:?}"}
             }
 
@@ -106,7 +197,7 @@ fn MainApp() -> Element {
                         pre { class: "term-rule", style: "padding-left: 15px", "{ind + 1}:" }
                         div {
                             class: "term-line",
-                            Term { term: Box::new(l), outer: true, index: Vec::new(), unselectable: true, other: false }
+                            Term { term: Ptr::new(l), outer: true, index: ProofPath::default(), unselectable: true, other: false }
                             div { class: "term-rule", "{logic_check::Instruction::Premise}" }
                         }
                     }
@@ -126,13 +217,28 @@ fn MainApp() -> Element {
     } else {
         "app-container app-container-info"
     };
-    rsx!(div { class, {body} })
+    rsx!(div { class, {body} HelpOverlay {} })
 }
 
+/// The number of today's puzzle, the same everywhere, see
+/// [`logic_check::schedule`].
 fn day_since_start() -> usize {
-    let date_str = "Wed, 7 Aug 2024 10:52:37 +0200";
-    let datetime = DateTime::parse_from_rfc2822(date_str).unwrap();
-    Local::now().signed_duration_since(datetime).num_days() as usize
+    schedule::day(Utc::now().timestamp())
+}
+
+/// The puzzle of a day, see [`day_since_start`].
+fn puzzle(day: usize) -> FitchProof<&'static str> {
+    let data = include_str!("../data.json");
+    let json = serde_json::from_str::<Vec<FitchProof<&str>>>(data).unwrap();
+    let schedule = serde_json::from_str::<Schedule>(include_str!("../schedule.json")).unwrap();
+    json[schedule.puzzle(day).unwrap()].clone()
+}
+
+/// The puzzle chosen with `?puzzle=N` in the URL.
+async fn puzzle_from_url() -> Option<usize> {
+    let mut script = eval(r#"dioxus.send(new URLSearchParams(location.search).get("puzzle"));"#);
+    let n = serde_json::from_value::<Option<String>>(script.recv().await.ok()?).ok()??;
+    n.parse().ok()
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -141,49 +247,227 @@ struct ErrorField(Signal<Option<String>>);
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct GlobalProof(Signal<FitchProof<&'static str>>);
 
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+struct ProofVerifier(Signal<Verifier<&'static str>>);
+
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct TermSelector(Signal<Option<Vec<usize>>>);
+struct TermSelector(Signal<Option<ProofPath>>);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct WonTime(Signal<Option<usize>>);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-struct StartTime(DateTime<Local>);
+struct StartTime(Signal<DateTime<Local>>);
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 struct InfoScreen(Signal<bool>);
 
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct HelpScreen(Signal<bool>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ArchiveScreen(Signal<bool>);
+
+/// The number of the puzzle being played, see [`day_since_start`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Puzzle(Signal<usize>);
+
+/// True while proving a sequent entered by the player instead of a puzzle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Sandbox(Signal<bool>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct SandboxScreen(Signal<bool>);
+
+/// The fastest win of the puzzle, including earlier plays.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BestTime(Signal<Option<usize>>);
+
+/// The best win of the puzzle, including earlier plays, which the
+/// statistics count.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct BestWin(Signal<Option<Win>>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ModeScreen(Signal<bool>);
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StatsScreen(Signal<bool>);
+
 #[derive(Debug, Clone, PartialEq, Eq, Copy)]
-#[allow(clippy::type_complexity)]
-struct UndoStack(Signal<Vec<(FitchProof<&'static str>, Option<Vec<usize>>)>>);
-impl UndoStack {
-    pub fn undo(&mut self) -> Option<(FitchProof<&'static str>, Option<Vec<usize>>)> {
-        self.0.write().pop()
+struct GameMode(Signal<Mode>);
+
+/// When the timer was paused, while it is. The proof is hidden meanwhile.
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+struct Paused(Signal<Option<DateTime<Local>>>);
+
+/// The puzzles of the time attack, if one is being played.
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+struct TimeAttack(Signal<Attack>);
+
+/// Issued by the server when the puzzle was started, and sent along with
+/// the result, see [`leaderboard::start_puzzle`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct StartToken(Signal<Option<String>>);
+
+/// The rank the server gave the win, once it has been submitted, see
+/// [`leaderboard::submit_result`].
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+struct SubmittedRank(Signal<Option<usize>>);
+
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+struct ProofHistory(Signal<History<&'static str>>);
+impl ProofHistory {
+    /// Applies `edit` to the proof and records it, with the selection from
+    /// before the edit.
+    pub fn edit<R>(
+        &mut self,
+        mut proof: Signal<FitchProof<&'static str>>,
+        selection: Option<ProofPath>,
+        edit: impl FnOnce(&mut SubProof<&'static str>) -> R,
+    ) -> R {
+        let before = proof.read().proof.clone();
+        let res = edit(&mut proof.write().proof);
+        self.0
+            .write()
+            .record(&before, &proof.read().proof, selection);
+        res
     }
-    pub fn push(&mut self, proof: FitchProof<&'static str>, index: Option<Vec<usize>>) {
-        let mut w = self.0.write();
-        w.push((proof, index));
-        if w.len() > 10 {
-            w.remove(0);
-        }
+}
+
+/// Starts over with a new proof, keeping the best time.
+fn use_new_game() -> impl FnMut(FitchProof<&'static str>) + Copy {
+    let StartTime(mut start_time) = use_context();
+    let WonTime(mut won_time) = use_context();
+    let Paused(mut paused) = use_context();
+    let SubmittedRank(mut rank) = use_context();
+    let mut next = use_next_puzzle();
+    move |new| {
+        next(new);
+        start_time.set(Local::now());
+        won_time.set(None);
+        paused.set(None);
+        rank.set(None);
+    }
+}
+
+/// Moves on to a new proof with the clock still running, as in a time
+/// attack.
+fn use_next_puzzle() -> impl FnMut(FitchProof<&'static str>) + Copy {
+    let GlobalProof(mut proof) = use_context();
+    let ProofHistory(mut history) = use_context();
+    let ProofVerifier(mut verifier) = use_context();
+    let TermSelector(mut selection) = use_context();
+    let ErrorField(mut error_field) = use_context();
+    move |new| {
+        *proof.write() = new;
+        *history.write() = History::new();
+        verifier.write().reset();
+        *selection.write() = Some(ProofPath::new(vec![0]));
+        error_field.set(None);
     }
 }
 
 #[allow(unused)]
 fn app() -> Element {
     let GlobalProof(mut sig) = use_context_provider(|| GlobalProof(Signal::new(empty())));
+    let StartTime(mut start_time) = use_context_provider(|| StartTime(Signal::new(Local::now())));
+    let WonTime(mut won_time) = use_context_provider(|| WonTime(Signal::new(None)));
+    let ProofHistory(mut history) =
+        use_context_provider(|| ProofHistory(Signal::new(History::new())));
+    let Puzzle(mut number) = use_context_provider(|| Puzzle(Signal::new(day_since_start())));
+    let BestTime(mut best_time) = use_context_provider(|| BestTime(Signal::new(None)));
+    let BestWin(mut best_win) = use_context_provider(|| BestWin(Signal::new(None)));
+    let Sandbox(mut sandbox) = use_context_provider(|| Sandbox(Signal::new(false)));
+    let StartToken(mut token) = use_context_provider(|| StartToken(Signal::new(None)));
+    let ProofVerifier(mut verifier) =
+        use_context_provider(|| ProofVerifier(Signal::new(Verifier::new())));
+    let ErrorField(mut error_field) = use_context_provider(|| ErrorField(Signal::new(None)));
+    let GameMode(mut mode) = use_context_provider(|| GameMode(Signal::new(Mode::Timed)));
+    let Paused(mut paused) = use_context_provider(|| Paused(Signal::new(None)));
+    use_context_provider(|| TimeAttack(Signal::new(Attack::new(0))));
+    use_context_provider(|| SubmittedRank(Signal::new(None)));
+    let mut loaded = use_signal(|| false);
     use_coroutine(move |_: UnboundedReceiver<()>| async move {
-        let data = include_str!("../data.json");
-        let json = serde_json::from_str::<Vec<FitchProof<&str>>>(data).unwrap();
-        // *sig.write() = json[0].clone();
-        *sig.write() = json[day_since_start() % json.len()].clone();
+        // A shared link is played like the sandbox, without being saved
+        match share::load().await {
+            Some(Ok(proof)) => {
+                *sig.write() = proof;
+                let _ = verifier.write().verify(&mut sig.write());
+                sandbox.set(true);
+                return;
+            }
+            Some(Err(e)) => error_field.set(Some(format!("Invalid link: {e}"))),
+            None => {}
+        }
+        let today = day_since_start();
+        let day = puzzle_from_url()
+            .await
+            .filter(|n| *n <= today)
+            .unwrap_or(today);
+        number.set(day);
+        let puzzle = puzzle(day);
+        match storage::load(day).await {
+            // Only continue a saved game if it is for the same puzzle
+            Some(game)
+                if game.proof.prepositions == puzzle.prepositions
+                    && game.proof.result == puzzle.result =>
+            {
+                if let Some(t) = game.start_time() {
+                    start_time.set(t);
+                }
+                won_time.set(game.won_time);
+                best_time.set(game.progress().best());
+                best_win.set(game.best);
+                token.set(game.token);
+                mode.set(game.mode);
+                paused.set(
+                    game.paused
+                        .and_then(DateTime::from_timestamp_millis)
+                        .map(|t| t.with_timezone(&Local)),
+                );
+                *history.write() = game.history;
+                *sig.write() = game.proof;
+            }
+            _ => *sig.write() = puzzle,
+        }
+        // Without a token the result can't go on the leaderboard, so a game
+        // saved without one gets a new one, even if its time then won't match
+        if token.read().is_none() {
+            token.set(leaderboard::start_puzzle(day).await.ok());
+        }
+        loaded.set(true);
+    });
+    use_effect(move || {
+        // A time attack is over once left, so it isn't saved
+        if !loaded() || sandbox() || matches!(mode(), Mode::TimeAttack { .. }) {
+            return;
+        }
+        let puzzle = number();
+        let game = SavedGame {
+            proof: sig.read().clone(),
+            history: history.read().recent(storage::SAVED_EDITS),
+            started: start_time.read().timestamp_millis(),
+            won_time: *won_time.read(),
+            best_time: *best_time.read(),
+            token: token.read().clone(),
+            mode: mode(),
+            paused: paused().map(|t| t.timestamp_millis()),
+            best: best_win(),
+        };
+        spawn(async move {
+            if let Err(e) = storage::save(puzzle, &game).await {
+                error_field.set(Some(format!("The game couldn't be saved: {e}")));
+            }
+        });
     });
-    use_context_provider(|| ErrorField(Signal::new(None)));
-    use_context_provider(|| TermSelector(Signal::new(Some(vec![0]))));
-    use_context_provider(|| StartTime(Local::now()));
+    use_context_provider(|| TermSelector(Signal::new(Some(ProofPath::new(vec![0])))));
     use_context_provider(|| InfoScreen(Signal::new(false)));
-    use_context_provider(|| WonTime(Signal::new(None)));
-    use_context_provider(|| UndoStack(Signal::new(Vec::new())));
+    use_context_provider(|| HelpScreen(Signal::new(false)));
+    use_context_provider(|| ArchiveScreen(Signal::new(false)));
+    use_context_provider(|| SandboxScreen(Signal::new(false)));
+    use_context_provider(|| ModeScreen(Signal::new(false)));
+    use_context_provider(|| StatsScreen(Signal::new(false)));
     let style = grass::include!("src/style.scss");
 
     rsx! {
@@ -0,0 +1,94 @@
+//! How a game is timed: against the clock for the leaderboard, without a
+//! clock for practice, or as a time attack through generated puzzles.
+
+use logic_check::{
+    generate::{self, Rng},
+    FitchProof, SubProof,
+};
+use serde::{Deserialize, Serialize};
+use std::fmt::Display;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum Mode {
+    /// Timed, and the first win goes on the leaderboard.
+    #[default]
+    Timed,
+    /// Without a timer, and never on the leaderboard.
+    Practice,
+    /// As many generated puzzles as possible before the time runs out.
+    TimeAttack { minutes: usize },
+}
+impl Mode {
+    /// The lengths of time attacks to choose from, in minutes.
+    pub const ATTACKS: [usize; 3] = [1, 3, 5];
+
+    /// Whether the timer can be paused. A timed game can't be, as the server
+    /// checks the time of a win against when it handed out the start token.
+    pub fn can_pause(self) -> bool {
+        matches!(self, Mode::TimeAttack { .. })
+    }
+
+    /// How long the game may take, in seconds.
+    pub fn time_limit(self) -> Option<usize> {
+        match self {
+            Mode::TimeAttack { minutes } => Some(minutes * 60),
+            _ => None,
+        }
+    }
+}
+impl Display for Mode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Mode::Timed => write!(f, "timed"),
+            Mode::Practice => write!(f, "practice"),
+            Mode::TimeAttack { minutes } => write!(f, "{minutes} minute time attack"),
+        }
+    }
+}
+
+/// The puzzles of a time attack, and how many of them have been solved.
+#[derive(Debug, Clone)]
+pub struct Attack {
+    rng: Rng,
+    pub solved: usize,
+}
+impl Attack {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: Rng::new(seed),
+            solved: 0,
+        }
+    }
+
+    /// The next puzzle, with a proof of two to five lines.
+    pub fn next_puzzle(&mut self) -> FitchProof<&'static str> {
+        loop {
+            let premises = self.rng.below(3);
+            let lines = 2 + self.rng.below(4);
+            let puzzle = generate::proof(&mut self.rng, &["p", "q", "r"], premises, lines);
+            // A result that is already a premise would be solved by copying it
+            if !puzzle.prepositions.iter().any(|p| *p == *puzzle.result) {
+                return FitchProof {
+                    proof: SubProof::default(),
+                    ..puzzle
+                };
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Pausing moves the start of the timer, which the server would take as
+    /// a made up time for a timed game.
+    #[test]
+    fn only_time_attacks_pause() {
+        assert!(!Mode::Timed.can_pause());
+        assert!(!Mode::Practice.can_pause());
+        for minutes in Mode::ATTACKS {
+            assert!(Mode::TimeAttack { minutes }.can_pause());
+        }
+    }
+}
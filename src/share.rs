@@ -0,0 +1,25 @@
+//! Links to puzzles and proofs, which carry them in the URL fragment, so
+//! that they can be shared without a server.
+
+use crate::util::intern;
+use dioxus::prelude::*;
+use logic_check::{share, FitchProof};
+
+/// Copies a link to the puzzle to the clipboard, with the proof so far if
+/// `with_proof` is set.
+pub fn copy_link(proof: &FitchProof<&'static str>, with_proof: bool) {
+    let code = share::encode(proof, with_proof);
+    eval(&format!(
+        "navigator.clipboard.writeText(location.origin + location.pathname + '#' + encodeURI({code:?}))"
+    ));
+}
+
+/// The puzzle or proof in the URL fragment, if there is one.
+pub async fn load() -> Option<Result<FitchProof<&'static str>, String>> {
+    let mut script = eval("dioxus.send(decodeURIComponent(location.hash.slice(1)));");
+    let code = serde_json::from_value::<String>(script.recv().await.ok()?).ok()?;
+    if code.is_empty() {
+        return None;
+    }
+    Some(share::decode(&code).map(|proof| proof.rename(&mut |v| intern(v))))
+}
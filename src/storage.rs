@@ -0,0 +1,229 @@
+//! Saves a game for every puzzle in the browser's local storage, so that
+//! reloading the page neither loses the proof nor restarts the timer.
+
+use crate::{mode::Mode, util::intern};
+use chrono::{DateTime, Local};
+use dioxus::prelude::*;
+use logic_check::{FitchProof, History};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A game as it is saved. It is loaded with owned variables, which are then
+/// interned for the game.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedGame<T = &'static str> {
+    pub proof: FitchProof<T>,
+    pub history: History<T>,
+    /// When the puzzle was started, in milliseconds since the Unix epoch.
+    pub started: i64,
+    pub won_time: Option<usize>,
+    /// The fastest win, which is kept when the puzzle is played again.
+    #[serde(default)]
+    pub best_time: Option<usize>,
+    /// The token the server issued when the puzzle was started.
+    #[serde(default)]
+    pub token: Option<String>,
+    /// How the game is timed, which is never a time attack.
+    #[serde(default)]
+    pub mode: Mode,
+    /// When the timer was paused, if it is, in milliseconds since the Unix
+    /// epoch.
+    #[serde(default)]
+    pub paused: Option<i64>,
+    /// The best win, which is kept when the puzzle is played again.
+    #[serde(default)]
+    pub best: Option<Win>,
+}
+impl SavedGame {
+    pub fn start_time(&self) -> Option<DateTime<Local>> {
+        DateTime::from_timestamp_millis(self.started).map(|t| t.with_timezone(&Local))
+    }
+
+    pub fn progress(&self) -> Progress {
+        Progress {
+            won_time: self.won_time,
+            best_time: self.best_time,
+            mode: self.mode,
+        }
+    }
+}
+impl SavedGame<String> {
+    /// The same game with its variables interned, like typed ones, so that
+    /// every name is only leaked once.
+    fn intern(self) -> SavedGame {
+        SavedGame {
+            proof: self.proof.rename(&mut |v| intern(v)),
+            history: self.history.rename(&mut |v| intern(v)),
+            started: self.started,
+            won_time: self.won_time,
+            best_time: self.best_time,
+            token: self.token,
+            mode: self.mode,
+            paused: self.paused,
+            best: self.best,
+        }
+    }
+}
+
+/// A saved game as the statistics look at it, without what only matters for
+/// playing it.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PlayedGame {
+    pub proof: FitchProof<String>,
+    pub history: History<String>,
+    pub started: i64,
+    pub won_time: Option<usize>,
+    #[serde(default)]
+    pub mode: Mode,
+    #[serde(default)]
+    pub best: Option<Win>,
+}
+impl PlayedGame {
+    /// The best win, or the game itself if it was won before the best win
+    /// was saved.
+    pub fn best(self) -> Option<Win> {
+        self.best.or_else(|| {
+            Some(Win {
+                time: self.won_time?,
+                started: self.started,
+                undos: self.history.undos(),
+                mode: self.mode,
+                proof: self.proof,
+            })
+        })
+    }
+}
+
+/// A finished game of a puzzle, as the statistics count it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Win {
+    pub proof: FitchProof<String>,
+    /// When the puzzle was started, in milliseconds since the Unix epoch.
+    pub started: i64,
+    pub time: usize,
+    pub undos: usize,
+    pub mode: Mode,
+}
+impl Win {
+    /// The better of two wins: timed wins beat untimed ones, and faster ones
+    /// slower ones.
+    pub fn best(a: Option<Win>, b: Win) -> Win {
+        let key = |w: &Win| (w.mode != Mode::Timed, w.time);
+        match a {
+            Some(a) if key(&a) <= key(&b) => a,
+            _ => b,
+        }
+    }
+}
+
+/// How far the player got with a puzzle, without the proof.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub struct Progress {
+    pub won_time: Option<usize>,
+    #[serde(default)]
+    pub best_time: Option<usize>,
+    #[serde(default)]
+    pub mode: Mode,
+}
+
+impl Progress {
+    /// The fastest win, if the puzzle has been solved. Wins in practice
+    /// don't have a time.
+    pub fn best(&self) -> Option<usize> {
+        let won = self.won_time.filter(|_| self.mode == Mode::Timed);
+        best_time(self.best_time, won)
+    }
+
+    pub fn solved(&self) -> bool {
+        self.won_time.is_some() || self.best_time.is_some()
+    }
+}
+
+/// The faster of two times.
+pub fn best_time(a: Option<usize>, b: Option<usize>) -> Option<usize> {
+    a.into_iter().chain(b).min()
+}
+
+const PREFIX: &str = "logiko-";
+
+fn key(puzzle: usize) -> String {
+    format!("{PREFIX}{puzzle}")
+}
+
+/// The saved game for a puzzle, if there is one.
+pub async fn load(puzzle: usize) -> Option<SavedGame> {
+    let mut script = eval("dioxus.send(localStorage.getItem(await dioxus.recv()));");
+    script.send(key(puzzle).into()).ok()?;
+    let json = serde_json::from_value::<Option<String>>(script.recv().await.ok()?).ok()??;
+    let game = serde_json::from_str::<SavedGame<String>>(&json).ok()?;
+    Some(game.intern())
+}
+
+/// The JSON of every saved game, by puzzle number.
+async fn load_all() -> Vec<(usize, String)> {
+    let mut script = eval(
+        r#"
+        const prefix = await dioxus.recv();
+        dioxus.send(Object.keys(localStorage)
+            .filter((k) => k.startsWith(prefix))
+            .map((k) => [k.slice(prefix.length), localStorage.getItem(k)]));
+        "#,
+    );
+    let _ = script.send(PREFIX.into());
+    let Ok(games) = script.recv().await else {
+        return Vec::new();
+    };
+    serde_json::from_value::<Vec<(String, String)>>(games)
+        .unwrap_or_default()
+        .into_iter()
+        .filter_map(|(n, game)| Some((n.parse().ok()?, game)))
+        .collect()
+}
+
+/// The progress of every puzzle that has been played, by puzzle number.
+pub async fn load_progress() -> HashMap<usize, Progress> {
+    load_all()
+        .await
+        .into_iter()
+        .filter_map(|(n, game)| Some((n, serde_json::from_str(&game).ok()?)))
+        .collect()
+}
+
+/// Every saved game, by puzzle number.
+pub async fn load_games() -> Vec<(usize, PlayedGame)> {
+    load_all()
+        .await
+        .into_iter()
+        .filter_map(|(n, game)| Some((n, serde_json::from_str(&game).ok()?)))
+        .collect()
+}
+
+/// How many edits of a game are saved, so that long games still fit in
+/// local storage.
+pub const SAVED_EDITS: usize = 200;
+
+/// Saves the game, or returns why the browser wouldn't, such as local
+/// storage being full.
+pub async fn save(puzzle: usize, game: &SavedGame) -> Result<(), String> {
+    let json = serde_json::to_string(game).map_err(|e| e.to_string())?;
+    let mut script = eval(
+        r#"
+        const [key, json] = [await dioxus.recv(), await dioxus.recv()];
+        try {
+            localStorage.setItem(key, json);
+            dioxus.send(null);
+        } catch (e) {
+            dioxus.send(String(e));
+        }
+        "#,
+    );
+    script
+        .send(key(puzzle).into())
+        .map_err(|e| format!("{e:?}"))?;
+    script.send(json.into()).map_err(|e| format!("{e:?}"))?;
+    let res = script.recv().await.map_err(|e| format!("{e:?}"))?;
+    match serde_json::from_value::<Option<String>>(res) {
+        Ok(Some(e)) => Err(e),
+        _ => Ok(()),
+    }
+}
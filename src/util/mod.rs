@@ -4,3 +4,20 @@ pub trait Droppable {
 impl<T> Droppable for T {
     fn drop(self) {}
 }
+
+thread_local! {
+    static NAMES: std::cell::RefCell<std::collections::HashSet<&'static str>> = Default::default();
+}
+
+/// The same `&'static str` for equal names, so that typed variables can be
+/// used in the proof. Every name is only leaked once.
+pub fn intern(name: &str) -> &'static str {
+    NAMES.with_borrow_mut(|names| match names.get(name) {
+        Some(n) => *n,
+        None => {
+            let n: &'static str = Box::leak(name.to_owned().into_boxed_str());
+            names.insert(n);
+            n
+        }
+    })
+}